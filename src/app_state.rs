@@ -0,0 +1,805 @@
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::fs::{self, File};
+use std::io::{self, Write, Read};
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Local, Utc};
+
+use crate::assignment::AssignmentPool;
+use crate::curve::CurvePolicy;
+use crate::deadline::{apply_deductions, late_deductions, LateDeduction, LatePolicy};
+use crate::grade::GradeScale;
+use crate::history::{History, Snapshot};
+use crate::parse_error::{parse_cell, parse_until_marker, ParseError, ParseErrorKind};
+use crate::paste::PasteImport;
+use crate::schema;
+use crate::score::{average, prorated_sum, Score};
+use crate::table_io::ImportPreview;
+use crate::table_view::TableViews;
+
+#[derive(Serialize, Deserialize)]
+pub struct Log {
+    time: DateTime<Local>,
+    function_info: String,
+    message: String,
+}
+
+impl Log {
+    pub fn new(info: String, message: String) -> Self {
+        Self {
+            time: Local::now(),
+            function_info: info,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Log {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = self.time.format("%Y-%m-%d %H:%M:%S");
+        write!(f, "{} - {} \n{}", time, self.function_info, self.message)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub enum TableType {
+    #[default]
+    FinalScore,
+    ProjectScore,
+    GroupList,
+}
+
+impl Clone for TableType {
+    fn clone(&self) -> Self {
+        match self {
+            TableType::FinalScore => TableType::FinalScore,
+            TableType::ProjectScore => TableType::ProjectScore,
+            TableType::GroupList => TableType::GroupList,
+        }
+    }
+}
+
+
+/// 导入分项成绩时写入的目标
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum ScoreTarget {
+    #[default]
+    Homework,
+    Discussion,
+}
+
+impl ScoreTarget {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScoreTarget::Homework => "作业",
+            ScoreTarget::Discussion => "讨论",
+        }
+    }
+}
+
+/// 导入 Project 成绩时写入的目标
+#[derive(Clone, Copy, PartialEq)]
+pub enum ProjectScoreTarget {
+    Report,
+    Pr,
+}
+
+impl ProjectScoreTarget {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectScoreTarget::Report => "报告成绩",
+            ProjectScoreTarget::Pr => "PR 成绩",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct GroupItem {
+    pub group_id: usize,
+    pub group_members: Vec<StudentItem>,
+}
+
+impl Default for GroupItem {
+    fn default() -> Self {
+        Self {
+            group_id: 0,
+            group_members: vec![
+                StudentItem {
+                    student_id: "".to_string(),
+                    student_name: "default".to_string(),
+                },
+                StudentItem {
+                    student_id: "".to_string(),
+                    student_name: "default".to_string(),
+                },
+                StudentItem {
+                    student_id: "".to_string(),
+                    student_name: "default".to_string(),
+                },
+            ],
+        }
+    }
+}
+
+impl GroupItem {
+    pub fn from_raw(raw_data: &[String]) -> Self {
+        let group_id = raw_data[0].parse::<usize>().unwrap_or(0);
+        let mut group_members = Vec::new();
+        for i in (1..raw_data.len()).step_by(2) {
+            if i+1 >= raw_data.len() {
+                break;
+            }
+            if raw_data[i].is_empty() {
+                continue;
+            }
+            group_members.push(StudentItem {
+                student_id: raw_data[i].clone(),
+                student_name: raw_data[i + 1].clone(),
+            });
+        }
+        Self {
+            group_id,
+            group_members,
+        }
+    }
+    pub fn to_vec(&self) -> Vec<String> {
+        let mut vec = vec![self.group_id.to_string()];
+        for member in &self.group_members {
+            vec.push(member.student_id.clone());
+            vec.push(member.student_name.clone());
+        }
+        vec
+    }
+
+    pub fn from_vec(vec: &[String]) -> Result<Self, ParseError> {
+        if vec.is_empty() {
+            return Err(ParseError::new(ParseErrorKind::Incomplete, 1, "组号", ""));
+        }
+
+        let group_id = parse_cell::<usize>(vec, 0, "组号")?;
+        let mut group_members = Vec::new();
+
+        for i in (1..vec.len()).step_by(2) {
+            if i + 1 >= vec.len() {
+                return Err(ParseError::new(ParseErrorKind::MissingName, i + 2, "姓名", &vec[i]));
+            }
+            group_members.push(StudentItem {
+                student_id: vec[i].clone(),
+                student_name: vec[i + 1].clone(),
+            });
+        }
+
+        Ok(Self {
+            group_id,
+            group_members,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub struct StudentItem {
+    pub student_id: String,
+    pub student_name: String,
+}
+
+/// Project 成绩的计算规则，随 Project 要求调整
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ProjectRules {
+    /// 展示组报告分所占比例
+    pub presentation_report_weight: f32,
+    /// 其余报告中最高分所占比例
+    pub best_report_weight: f32,
+    /// 其余报告折算为 Bonus 时的除数
+    pub bonus_divisor: f32,
+    /// PR 总分 = PR 平均分 / pr_divisor
+    pub pr_divisor: f32,
+    /// 每组提交的报告数量
+    pub report_count: usize,
+    /// 每组的 PR 评审人数
+    pub pr_reviewer_count: usize,
+    /// 未提交的报告/PR 是否按 0 分计入，否则与豁免一样不计入
+    pub missing_as_zero: bool,
+    /// 每份报告的满分
+    pub report_full_mark: f32,
+    /// 每次 PR 评审的满分
+    pub pr_full_mark: f32,
+    /// 展示分数的满分
+    pub presentation_full_mark: f32,
+}
+
+impl Default for ProjectRules {
+    fn default() -> Self {
+        Self {
+            presentation_report_weight: 0.5,
+            best_report_weight: 0.5,
+            bonus_divisor: 20.0,
+            pr_divisor: 10.0,
+            report_count: 3,
+            pr_reviewer_count: 3,
+            missing_as_zero: true,
+            report_full_mark: 20.0,
+            pr_full_mark: 40.0,
+            presentation_full_mark: 6.0,
+        }
+    }
+}
+
+impl ProjectRules {
+    /// Report 总分的满分：展示组报告与其余报告最高分按占比计入
+    pub fn report_total_full_mark(&self) -> f32 {
+        self.report_full_mark * (self.presentation_report_weight + self.best_report_weight)
+    }
+
+    /// PR 总分的满分
+    pub fn pr_total_full_mark(&self) -> f32 {
+        self.pr_full_mark / self.pr_divisor
+    }
+
+    /// Project 总分的满分
+    pub fn total_full_mark(&self) -> f32 {
+        self.report_total_full_mark() + self.pr_total_full_mark() + self.presentation_full_mark
+    }
+}
+
+/// `ProjectItem::to_vec` 中前 7 列的字段名，也是 CSV 格式的表头
+pub const PROJECT_FIELDS: [&str; 7] = ["组号", "Project 总分", "展示分数", "Bonus", "Report 总分", "PR 总分", "展示报告 ID"];
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProjectItem {
+    pub group_id: usize,
+    pub total_score: f32,
+    pub presentation_score: f32,
+    pub bonus_score: f32,
+    pub report_score_sum: f32,
+    pub pr_score_sum: f32,
+    pub presentation_id: usize,
+    pub report_score: Vec<Score>,
+    pub pr_score: Vec<Score>,
+}
+
+impl Default for ProjectItem {
+    fn default() -> Self {
+        Self {
+            group_id: 0,
+            total_score: 0.0,
+            presentation_score: 0.0,
+            bonus_score: 0.0,
+            report_score_sum: 0.0,
+            pr_score_sum: 0.0,
+            presentation_id: 0,
+            report_score: vec![Score::default(); 3],
+            pr_score: vec![Score::default(); 3],
+        }
+    }
+}
+
+impl ProjectItem {
+    pub fn new(group_id: usize, rules: &ProjectRules) -> Self {
+        Self {
+            group_id,
+            report_score: vec![Score::default(); rules.report_count],
+            pr_score: vec![Score::default(); rules.pr_reviewer_count],
+            ..Default::default()
+        }
+    }
+
+    pub fn calaculate(&mut self, rules: &ProjectRules) {
+        self.pr_score_sum = average(&self.pr_score, rules.missing_as_zero) / rules.pr_divisor;
+        self.report_score_sum = 0.0;
+        let (mut max_score, mut sum_score): (f32, f32) = (0.0, 0.0);
+        for (index, score) in self.report_score.iter().enumerate() {
+            // 豁免的报告不计入
+            let Some(score) = score.counted(rules.missing_as_zero) else {
+                continue;
+            };
+            if index + 1 == self.presentation_id {
+                self.report_score_sum += score * rules.presentation_report_weight;
+            } else {
+                max_score = max_score.max(score);
+                sum_score += score;
+            }
+        }
+        sum_score -= max_score;
+        self.bonus_score = sum_score / rules.bonus_divisor;
+        self.report_score_sum += max_score * rules.best_report_weight;
+        self.total_score = self.report_score_sum + self.pr_score_sum + self.presentation_score;
+    }
+
+    /// 检查报告数量与 PR 评审人数是否与规则一致
+    pub fn matches_rules(&self, rules: &ProjectRules) -> bool {
+        self.report_score.len() == rules.report_count && self.pr_score.len() == rules.pr_reviewer_count
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        let mut vec = vec![self.group_id.to_string()];
+        vec.push(self.total_score.to_string());
+        vec.push(self.presentation_score.to_string());
+        vec.push(self.bonus_score.to_string());
+        vec.push(self.report_score_sum.to_string());
+        vec.push(self.pr_score_sum.to_string());
+        vec.push(self.presentation_id.to_string());
+        for score in &self.report_score {
+            vec.push(score.to_string());
+        }
+        vec.push("reportEnd".to_string());
+        for score in &self.pr_score {
+            vec.push(score.to_string());
+        }
+        vec.push("prEnd".to_string());
+        vec
+    }
+
+    pub fn from_vec(vec: &[String]) -> Result<Self, ParseError> {
+        if vec.len() < 8 {
+            let field = PROJECT_FIELDS.get(vec.len()).copied().unwrap_or("Report 分项");
+            return Err(ParseError::new(ParseErrorKind::Incomplete, vec.len() + 1, field, ""));
+        }
+
+        let group_id = parse_cell(vec, 0, PROJECT_FIELDS[0])?;
+        let total_score = parse_cell(vec, 1, PROJECT_FIELDS[1])?;
+        let presentation_score = parse_cell(vec, 2, PROJECT_FIELDS[2])?;
+        let bonus_score = parse_cell(vec, 3, PROJECT_FIELDS[3])?;
+        let report_score_sum = parse_cell(vec, 4, PROJECT_FIELDS[4])?;
+        let pr_score_sum = parse_cell(vec, 5, PROJECT_FIELDS[5])?;
+        let presentation_id = parse_cell(vec, 6, PROJECT_FIELDS[6])?;
+
+        let (report_score, marker) = parse_until_marker(vec, 7, "reportEnd", "Report 分项")?;
+        let (pr_score, _) = parse_until_marker(vec, marker + 1, "prEnd", "PR 分项")?;
+
+        Ok(Self {
+            group_id,
+            total_score,
+            presentation_score,
+            bonus_score,
+            report_score_sum,
+            pr_score_sum,
+            presentation_id,
+            report_score,
+            pr_score,
+        })
+    }
+}
+
+/// 期末、期中考试的满分
+pub const EXAM_FULL_MARK: f32 = 100.0;
+
+/// 总评成绩的计算规则，每学期可以在设置面板中调整
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct GradingPolicy {
+    /// 没有讨论分项信息时，讨论分 = 讨论分项平均值 * discussion_multiplier
+    pub discussion_multiplier: f32,
+    /// 没有作业分项信息时，作业分 = 作业分项之和 / homework_divisor
+    pub homework_divisor: f32,
+    /// 期中分 = max(期末, 期中) / midterm_divisor
+    pub midterm_divisor: f32,
+    /// 平时分上限
+    pub general_score_cap: f32,
+    /// 期末考试低于该分数时总分记为 0
+    pub final_exam_threshold: f32,
+    /// 期末考试占总分的百分比
+    pub final_exam_weight: f32,
+    /// 未提交的作业/讨论是否按 0 分计入，否则与豁免一样不计入
+    pub missing_as_zero: bool,
+    /// 讨论分项的满分、权重与去掉最低分的规则
+    pub discussion: AssignmentPool,
+    /// 作业分项的满分、权重、截止时间与去掉最低分的规则
+    pub homework: AssignmentPool,
+    /// 作业迟交的扣分规则
+    pub late_penalty: LatePolicy,
+}
+
+impl Default for GradingPolicy {
+    fn default() -> Self {
+        Self {
+            discussion_multiplier: 10.0,
+            homework_divisor: 25.0,
+            midterm_divisor: 10.0,
+            general_score_cap: 60.0,
+            final_exam_threshold: 40.0,
+            final_exam_weight: 40.0,
+            missing_as_zero: true,
+            discussion: AssignmentPool::default(),
+            homework: AssignmentPool::default(),
+            late_penalty: LatePolicy::default(),
+        }
+    }
+}
+
+impl GradingPolicy {
+    /// 讨论分：填写了分项信息时按加权得分率折算，否则为平均值乘以倍数
+    pub fn discussion_total(&self, scores: &[Score]) -> f32 {
+        if self.discussion.is_configured() {
+            self.discussion.total(scores, self.missing_as_zero)
+        } else {
+            average(scores, self.missing_as_zero) * self.discussion_multiplier
+        }
+    }
+
+    /// 讨论分的满分，只有按分项信息加权折算时才确定
+    pub fn discussion_full_mark(&self) -> Option<f32> {
+        self.discussion.is_configured().then_some(self.discussion.full_mark)
+    }
+
+    /// 作业分的满分，只有按分项信息加权折算时才确定
+    pub fn homework_full_mark(&self) -> Option<f32> {
+        self.homework.is_configured().then_some(self.homework.full_mark)
+    }
+
+    /// 作业分：填写了分项信息时按加权得分率折算，否则为分项之和除以除数
+    pub fn homework_total(&self, scores: &[Score]) -> f32 {
+        if self.homework.is_configured() {
+            self.homework.total(scores, self.missing_as_zero)
+        } else {
+            prorated_sum(scores, self.missing_as_zero) / self.homework_divisor
+        }
+    }
+}
+
+/// `FinalItem::to_vec` 中前 10 列的字段名，也是 CSV 格式的表头
+pub const FINAL_FIELDS: [&str; 10] = ["学号", "姓名", "总分", "平时分", "期末", "Project", "期中", "作业", "讨论", "Bonus"];
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct FinalItem {
+    pub student_info: StudentItem,
+    pub final_score: f32,
+    pub general_score: f32,
+    pub final_exam_score: Score,
+    pub project_score_sum: f32,
+    pub midterm_score: Score,
+    pub homework_score_sum: f32,
+    pub discussion_score_sum: f32,
+    pub bonus_score: f32,
+    pub discussion_score: Vec<Score>,
+    pub homework_score: Vec<Score>,
+    /// 按已应用的调分曲线调整后的总分，未调分时为 None
+    #[serde(default)]
+    pub adjusted_score: Option<f32>,
+    /// 五级制等级与绩点，由报送的百分制成绩换算
+    #[serde(default)]
+    pub grade_level: String,
+    #[serde(default)]
+    pub grade_point: f32,
+    /// 每次作业的提交时间，与 `homework_score` 一一对应，没有记录时为 None
+    #[serde(default)]
+    pub homework_submitted: Vec<Option<DateTime<Utc>>>,
+}
+
+impl Default for FinalItem {
+    fn default() -> Self {
+        Self {
+            student_info: StudentItem {
+                student_id: "".to_string(),
+                student_name: "default".to_string(),
+            },
+            final_score: 0.0,
+            general_score: 0.0,
+            final_exam_score: Score::default(),
+            project_score_sum: 0.0,
+            midterm_score: Score::default(),
+            homework_score_sum: 0.0,
+            discussion_score_sum: 0.0,
+            bonus_score: 0.0,
+            discussion_score: vec![Score::default(); 3],
+            homework_score: vec![Score::default(); 3],
+            adjusted_score: None,
+            grade_level: String::new(),
+            grade_point: 0.0,
+            homework_submitted: vec![],
+        }
+    }
+}
+
+impl FinalItem {
+    pub fn from_id_name(raw_data: &[String]) -> Self {
+        let mut final_item = Self::default();
+        if raw_data.len() < 2 {
+            return final_item;
+        }
+        final_item.student_info.student_id = raw_data[0].clone();
+        final_item.student_info.student_name = raw_data[1].clone();
+        final_item
+    }
+    /// 作业先扣除迟交分数；豁免的分项不参与平均；期末缺考、豁免(缓考)或未录入时总分记为 0，等级中另行标出
+    pub fn calaculate(&mut self, policy: &GradingPolicy) {
+        self.discussion_score_sum = policy.discussion_total(&self.discussion_score);
+        self.homework_score_sum = policy.homework_total(&self.late_homework_score(policy));
+        let final_exam = self.final_exam_score.counted(true).unwrap_or(0.0);
+        let midterm = self.midterm_score.counted(true).unwrap_or(0.0);
+        self.general_score = self.discussion_score_sum 
+                            + self.homework_score_sum 
+                            + final_exam.max(midterm) / policy.midterm_divisor
+                            + self.project_score_sum
+                            + self.bonus_score;
+        self.general_score = self.general_score.min(policy.general_score_cap);
+        self.final_score = if !self.final_exam_score.is_scored() || final_exam < policy.final_exam_threshold {
+            0.0
+        } else {
+            self.general_score + final_exam / EXAM_FULL_MARK * policy.final_exam_weight
+        }
+    }
+
+    /// 作业的迟交扣分
+    pub fn late_deductions(&self, policy: &GradingPolicy) -> Vec<LateDeduction> {
+        late_deductions(&self.homework_score, &self.homework_submitted, &policy.homework, &policy.late_penalty)
+    }
+
+    /// 扣除迟交分数后的作业分项成绩
+    pub fn late_homework_score(&self, policy: &GradingPolicy) -> Vec<Score> {
+        apply_deductions(&self.homework_score, &self.late_deductions(policy))
+    }
+
+    /// 期末考试没有分数时的标记，如 "缺考"、"缓考"
+    pub fn exam_flag(&self) -> Option<&'static str> {
+        match self.final_exam_score {
+            Score::Scored(_) => None,
+            Score::Missing => Some("缺成绩"),
+            Score::Excused => Some("缓考"),
+            Score::Absent => Some("缺考"),
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        let mut vec = vec![self.student_info.student_id.clone(), self.student_info.student_name.clone()];
+        vec.push(self.final_score.to_string());
+        vec.push(self.general_score.to_string());
+        vec.push(self.final_exam_score.to_string());
+        vec.push(self.project_score_sum.to_string());
+        vec.push(self.midterm_score.to_string());
+        vec.push(self.homework_score_sum.to_string());
+        vec.push(self.discussion_score_sum.to_string());
+        vec.push(self.bonus_score.to_string());
+        for score in &self.discussion_score {
+            vec.push(score.to_string());
+        }
+        vec.push("discussionEnd".to_string());
+        for score in &self.homework_score {
+            vec.push(score.to_string());
+        }
+        vec.push("homeworkEnd".to_string());
+        vec
+    }
+
+    pub fn from_vec(vec: &[String]) -> Result<Self, ParseError> {
+        if vec.len() < 10 {
+            let field = FINAL_FIELDS.get(vec.len()).copied().unwrap_or("讨论分项");
+            return Err(ParseError::new(ParseErrorKind::Incomplete, vec.len() + 1, field, ""));
+        }
+        let student_info = StudentItem {
+            student_id: vec[0].clone(),
+            student_name: vec[1].clone(),
+        };
+        let final_score = parse_cell(vec, 2, FINAL_FIELDS[2])?;
+        let general_score = parse_cell(vec, 3, FINAL_FIELDS[3])?;
+        let final_exam_score = parse_cell(vec, 4, FINAL_FIELDS[4])?;
+        let project_score_sum = parse_cell(vec, 5, FINAL_FIELDS[5])?;
+        let midterm_score = parse_cell(vec, 6, FINAL_FIELDS[6])?;
+        let homework_score_sum = parse_cell(vec, 7, FINAL_FIELDS[7])?;
+        let discussion_score_sum = parse_cell(vec, 8, FINAL_FIELDS[8])?;
+        let bonus_score = parse_cell(vec, 9, FINAL_FIELDS[9])?;
+
+        let (discussion_score, marker) = parse_until_marker(vec, 10, "discussionEnd", "讨论分项")?;
+        let (homework_score, _) = parse_until_marker(vec, marker + 1, "homeworkEnd", "作业分项")?;
+        Ok(Self {
+            student_info,
+            final_score,
+            general_score,
+            final_exam_score,
+            project_score_sum,
+            midterm_score,
+            homework_score_sum,
+            discussion_score_sum,
+            bonus_score,
+            discussion_score,
+            homework_score,
+            adjusted_score: None,
+            grade_level: String::new(),
+            grade_point: 0.0,
+            homework_submitted: vec![],
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct AppState {
+    // 在这里添加你需要存储的应用程序信息
+    pub input_text: String,
+    pub selected_k: usize,
+    pub group_size: usize,
+    pub output_text: Vec<Log>,
+    pub table_type: TableType,
+    pub final_table: Vec<FinalItem>,
+    pub project_table: Vec<ProjectItem>,
+    pub group_table: Vec<GroupItem>,
+    #[serde(default)]
+    pub grading_policy: GradingPolicy,
+    #[serde(default)]
+    pub project_rules: ProjectRules,
+    #[serde(default)]
+    pub export_path: String,
+    #[serde(default)]
+    pub import_path: String,
+    #[serde(default)]
+    pub import_target: ScoreTarget,
+    #[serde(default)]
+    pub import_item_index: usize,
+    #[serde(default)]
+    pub project_report_path: String,
+    #[serde(default)]
+    pub project_pr_path: String,
+    /// 三张表各自的排序、筛选与搜索状态
+    #[serde(default)]
+    pub table_views: TableViews,
+    /// 等级分布要求与已应用的调分曲线
+    #[serde(default)]
+    pub curve: CurvePolicy,
+    /// 百分制到五级制与绩点的换算规则
+    #[serde(default)]
+    pub grade_scale: GradeScale,
+    /// 启动时读取数据失败的原因，界面中提示用户
+    #[serde(skip)]
+    pub load_error: Option<String>,
+    /// 撤销/重做记录，只保存在内存中
+    #[serde(skip)]
+    pub history: History,
+    /// 等待确认的导入
+    #[serde(skip)]
+    pub import_preview: Option<ImportPreview>,
+    /// 最近一次导入时输入区中解析失败的行，输入区内容变化后清空
+    #[serde(skip)]
+    pub input_errors: Vec<ParseError>,
+    /// 等待确认列对应关系的粘贴数据
+    #[serde(skip)]
+    pub paste_import: Option<PasteImport>,
+    /// 正在编辑的表格单元格开始编辑前的数据，编辑结束时写入历史记录
+    #[serde(skip)]
+    pub table_edit: Option<Snapshot>,
+    /// 数据表的修改次数，界面据此判断缓存是否过期
+    #[serde(skip)]
+    pub revision: u64,
+    /// 自上次保存以来是否有需要写盘的修改，由自动保存读取并清除
+    #[serde(skip)]
+    pub dirty: bool,
+}
+
+impl AppState {
+    /// 执行一次可撤销的修改。修改前后输入区、三张表与调分曲线均未变化时不产生记录
+    pub fn edit<R>(&mut self, label: &str, f: impl FnOnce(&mut AppState) -> R) -> R {
+        let before = Snapshot::take(self);
+        let result = f(self);
+        // 不在快照中的数据(如计算规则)也可能被修改，一律等待保存
+        self.dirty = true;
+        if before.input_text != self.input_text {
+            self.input_errors.clear();
+        }
+        if before != Snapshot::take(self) {
+            self.history.push(label, before);
+            self.revision += 1;
+        }
+        result
+    }
+
+    /// 标记数据已修改，等待自动保存
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// 在输出区域追加一条记录
+    pub fn log(&mut self, info: String, message: String) {
+        self.output_text.push(Log::new(info, message));
+        self.dirty = true;
+    }
+
+    /// 按当前总评计算规则重新计算总评成绩表，并更新调整后总分、五级制等级与绩点
+    pub fn calculate_final_table(&mut self) {
+        let policy = &self.grading_policy;
+        for row in self.final_table.iter_mut() {
+            row.calaculate(policy);
+        }
+        self.update_reported_scores();
+    }
+
+    /// 按当前 Project 规则重新计算 Project 成绩表，返回报告/PR 数量与规则不一致的组号
+    pub fn calculate_project_table(&mut self) -> Vec<usize> {
+        let rules = &self.project_rules;
+        let mut mismatched = vec![];
+        for row in self.project_table.iter_mut() {
+            row.calaculate(rules);
+            if !row.matches_rules(rules) {
+                mismatched.push(row.group_id);
+            }
+        }
+        mismatched
+    }
+}
+
+pub const STATE_PATH: &str = "app_state.json";
+
+/// 读取 app_state.json 失败的原因
+#[derive(Debug)]
+pub enum StateError {
+    Io(io::Error),
+    /// 文件无法解析，原文件已备份到 `backup`
+    Corrupt { message: String, backup: String },
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::Io(e) => write!(f, "无法读取 {}: {}", STATE_PATH, e),
+            StateError::Corrupt { message, backup } => {
+                write!(f, "无法解析 {}: {}\n原文件已备份到 {}", STATE_PATH, message, backup)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for StateError {
+    fn from(e: io::Error) -> Self {
+        StateError::Io(e)
+    }
+}
+
+pub struct AppSingleton;
+
+impl AppSingleton {
+    /// 全局状态。读取失败时以空状态启动，错误写入 `load_error` 与输出区域
+    pub fn instance() -> Arc<Mutex<AppState>> {
+        static SINGLETON: OnceLock<Arc<Mutex<AppState>>> = OnceLock::new();
+
+        SINGLETON
+            .get_or_init(|| {
+                let state = AppSingleton::load_state().unwrap_or_else(|e| {
+                    let mut state = AppState::default();
+                    state.output_text.push(Log::new("读取数据".to_string(), e.to_string()));
+                    state.load_error = Some(e.to_string());
+                    state
+                });
+                Arc::new(Mutex::new(state))
+            })
+            .clone()
+    }
+
+    /// 读取并迁移 app_state.json。文件不存在或为空时返回空状态；
+    /// 无法解析时先备份原文件再返回错误，不会用默认值覆盖
+    pub fn load_state() -> Result<AppState, StateError> {
+        let mut file = match File::open(STATE_PATH) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(AppState::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut data = String::new();
+        file.read_to_string(&mut data)?;
+        if data.trim().is_empty() {
+            return Ok(AppState::default());
+        }
+        schema::decode_state(&data).map_err(|message| match Self::backup_state() {
+            Ok(backup) => StateError::Corrupt { message, backup },
+            Err(e) => StateError::Io(e),
+        })
+    }
+
+    fn backup_state() -> io::Result<String> {
+        let stem = format!("{}.bak-{}", STATE_PATH, Local::now().format("%Y%m%d-%H%M%S"));
+        let mut backup = stem.clone();
+        let mut index = 1;
+        while Path::new(&backup).exists() {
+            backup = format!("{}-{}", stem, index);
+            index += 1;
+        }
+        fs::copy(STATE_PATH, &backup)?;
+        Ok(backup)
+    }
+
+    /// 先写入临时文件再重命名，写入中途崩溃不会损坏原文件
+    pub fn save_state(state: &AppState) -> io::Result<()> {
+        let data = schema::encode_state(state)?;
+        let temp_path = format!("{}.tmp", STATE_PATH);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(data.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, STATE_PATH)
+    }
+}
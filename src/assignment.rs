@@ -0,0 +1,195 @@
+//! 作业与讨论的分项信息。
+//!
+//! 每个分项记录名称、满分、权重以及是否可以被去掉。作业分与讨论分按各分项得分率的加权平均折算到满分，
+//! 满分不同的分项因此不会被错误加权；去掉最低分时，在可以去掉的分项中按得分率去掉最低的若干项。
+//! 没有填写分项信息或关闭了加权折算时仍按 `GradingPolicy` 中原来的除数与倍数计算。
+//! 作业分项还可以填写截止时间，迟交的扣分见 `deadline` 模块；截止时间不受是否加权折算影响。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::score::Score;
+
+/// 一次作业或讨论
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Assignment {
+    pub name: String,
+    /// 满分
+    pub max_points: f32,
+    /// 在同类分项中的权重
+    pub weight: f32,
+    /// 是否参与去掉最低分
+    pub droppable: bool,
+    /// 截止时间，None 表示不检查迟交
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+impl Default for Assignment {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            max_points: 10.0,
+            weight: 1.0,
+            droppable: true,
+            deadline: None,
+        }
+    }
+}
+
+/// 一类分项(作业或讨论)的分项信息与折算方式
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct AssignmentPool {
+    /// 第 i 项对应成绩表中的第 i 个分项
+    pub assignments: Vec<Assignment>,
+    /// 去掉可以去掉的分项中得分率最低的项数
+    pub drop_lowest: usize,
+    /// 折算后的满分
+    pub full_mark: f32,
+    /// 是否按分项信息加权折算，关闭时分项信息只提供名称与截止时间
+    pub weighted: bool,
+}
+
+impl Default for AssignmentPool {
+    fn default() -> Self {
+        Self {
+            assignments: vec![],
+            drop_lowest: 0,
+            full_mark: 10.0,
+            weighted: true,
+        }
+    }
+}
+
+impl AssignmentPool {
+    /// 是否按分项信息加权折算，否则按原来的除数与倍数计算
+    pub fn is_configured(&self) -> bool {
+        self.weighted && !self.assignments.is_empty()
+    }
+
+    /// 计入成绩的分项：(序号, 得分率, 权重)。没有分项信息、满分或权重不为正的分项不计入
+    fn counted(&self, scores: &[Score], missing_as_zero: bool) -> Vec<(usize, f32, f32)> {
+        scores
+            .iter()
+            .zip(&self.assignments)
+            .enumerate()
+            .filter(|(_, (_, assignment))| assignment.max_points > 0.0 && assignment.weight > 0.0)
+            .filter_map(|(index, (score, assignment))| {
+                let value = score.counted(missing_as_zero)?;
+                Some((index, value / assignment.max_points, assignment.weight))
+            })
+            .collect()
+    }
+
+    /// 被去掉的分项序号。至少保留一个计入成绩的分项
+    pub fn dropped(&self, scores: &[Score], missing_as_zero: bool) -> Vec<usize> {
+        let counted = self.counted(scores, missing_as_zero);
+        let mut droppable: Vec<(usize, f32)> = counted
+            .iter()
+            .filter(|(index, _, _)| self.assignments[*index].droppable)
+            .map(|&(index, rate, _)| (index, rate))
+            .collect();
+        droppable.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let count = self.drop_lowest.min(droppable.len()).min(counted.len().saturating_sub(1));
+        droppable.into_iter().take(count).map(|(index, _)| index).collect()
+    }
+
+    /// 去掉最低分后各分项得分率的加权平均乘以满分，没有计入的分项时为 0
+    pub fn total(&self, scores: &[Score], missing_as_zero: bool) -> f32 {
+        let dropped = self.dropped(scores, missing_as_zero);
+        let (sum, weights) = self
+            .counted(scores, missing_as_zero)
+            .into_iter()
+            .filter(|(index, _, _)| !dropped.contains(index))
+            .fold((0.0, 0.0), |(sum, weights), (_, rate, weight)| (sum + rate * weight, weights + weight));
+        if weights > 0.0 {
+            sum / weights * self.full_mark
+        } else {
+            0.0
+        }
+    }
+
+    /// 补全分项信息到 `count` 项，新增的分项名为 `prefix` 加序号
+    pub fn fill(&mut self, count: usize, prefix: &str) {
+        for index in self.assignments.len()..count {
+            self.assignments.push(Assignment {
+                name: format!("{}{}", prefix, index + 1),
+                ..Assignment::default()
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(items: &[(f32, f32, bool)], drop_lowest: usize) -> AssignmentPool {
+        AssignmentPool {
+            assignments: items
+                .iter()
+                .map(|&(max_points, weight, droppable)| Assignment {
+                    max_points,
+                    weight,
+                    droppable,
+                    ..Assignment::default()
+                })
+                .collect(),
+            drop_lowest,
+            full_mark: 10.0,
+            weighted: true,
+        }
+    }
+
+    #[test]
+    fn total_weights_score_rates_not_raw_points() {
+        // 得分率 50% 与 100%，权重 1:3
+        let pool = pool(&[(10.0, 1.0, true), (100.0, 3.0, true)], 0);
+        let scores = [Score::Scored(5.0), Score::Scored(100.0)];
+        assert_eq!(pool.total(&scores, true), 8.75);
+    }
+
+    #[test]
+    fn excused_and_missing_items_follow_the_rule() {
+        let pool = pool(&[(10.0, 1.0, true), (10.0, 1.0, true)], 0);
+        assert_eq!(pool.total(&[Score::Scored(8.0), Score::Excused], true), 8.0);
+        assert_eq!(pool.total(&[Score::Scored(8.0), Score::Missing], true), 4.0);
+        assert_eq!(pool.total(&[Score::Scored(8.0), Score::Missing], false), 8.0);
+        assert_eq!(pool.total(&[Score::Excused, Score::Excused], true), 0.0);
+    }
+
+    #[test]
+    fn dropped_skips_non_droppable_items_and_keeps_one() {
+        let pool = pool(&[(10.0, 1.0, false), (10.0, 1.0, true), (20.0, 1.0, true)], 1);
+        // 第 0 项得分率最低但不能去掉，去掉可去掉的项中得分率最低的第 2 项
+        let scores = [Score::Scored(1.0), Score::Scored(9.0), Score::Scored(10.0)];
+        assert_eq!(pool.dropped(&scores, true), vec![2]);
+        assert_eq!(pool.total(&scores, true), 5.0);
+
+        let pool = AssignmentPool {
+            drop_lowest: 5,
+            ..pool
+        };
+        // 只有两项计入时最多去掉一项
+        let scores = [Score::Excused, Score::Scored(4.0), Score::Scored(10.0)];
+        assert_eq!(pool.dropped(&scores, true), vec![1]);
+        assert_eq!(pool.total(&scores, true), 5.0);
+    }
+
+    #[test]
+    fn items_without_metadata_or_positive_weight_are_ignored() {
+        let pool = pool(&[(10.0, 1.0, true), (10.0, 0.0, true), (0.0, 1.0, true)], 0);
+        let scores = [Score::Scored(6.0), Score::Scored(0.0), Score::Scored(5.0), Score::Scored(0.0)];
+        assert_eq!(pool.total(&scores, true), 6.0);
+    }
+
+    #[test]
+    fn fill_adds_named_items_up_to_count() {
+        let mut pool = pool(&[(5.0, 1.0, true)], 0);
+        pool.fill(3, "作业");
+        let names: Vec<&str> = pool.assignments.iter().map(|assignment| assignment.name.as_str()).collect();
+        assert_eq!(names, ["", "作业2", "作业3"]);
+        assert_eq!(pool.assignments[0].max_points, 5.0);
+    }
+}
@@ -0,0 +1,167 @@
+use std::fs;
+use std::io::{self, Read};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use ads::app_state::*;
+use ads::export::{export_xlsx, DEFAULT_EXPORT_PATH};
+use ads::importer::{import_item_scores, import_project_scores, read_sheet};
+use ads::random::{group_randomly, pick_random};
+use ads::sync::sync_project_scores;
+use ads::table_io::preview_import;
+
+/// ADS 助教终端。不带子命令时打开图形界面，子命令在当前目录的 app_state.json 上运行
+#[derive(Parser)]
+#[command(name = "ads")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// 导入数据到成绩表
+    Import {
+        #[arg(value_enum)]
+        kind: ImportKind,
+        /// 数据文件路径
+        file: String,
+        /// 作业/讨论: 将 "总分" 列写入第 N 个分项，0 表示按列导入全部分项
+        #[arg(long, default_value_t = 0)]
+        item: usize,
+    },
+    /// 按当前计算规则重新计算 Project 与总评成绩
+    Compute {
+        /// 计算总评前先按组队名单同步 Project 成绩
+        #[arg(long)]
+        sync_project: bool,
+    },
+    /// 导出三张表到 Excel
+    Export {
+        #[arg(default_value = DEFAULT_EXPORT_PATH)]
+        path: String,
+    },
+    /// 从名单中随机抽取，名单从文件或标准输入读取
+    RandomPick {
+        #[arg(short, default_value_t = 1)]
+        k: usize,
+        file: Option<String>,
+    },
+    /// 将名单随机分组，名单从文件或标准输入读取
+    RandomGroup {
+        #[arg(long, default_value_t = 3)]
+        size: usize,
+        file: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportKind {
+    /// 总评成绩表(输入区格式)
+    Final,
+    /// Project 成绩表(输入区格式)
+    Project,
+    /// 组队名单(输入区格式)
+    Group,
+    /// PTA 导出的作业成绩
+    Homework,
+    /// PTA 导出的讨论成绩
+    Discussion,
+    /// Project 报告评分表
+    Report,
+    /// Project 互评(PR)表
+    Pr,
+}
+
+fn read_input(file: Option<&str>) -> io::Result<String> {
+    match file {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut data = String::new();
+            io::stdin().read_to_string(&mut data)?;
+            Ok(data)
+        }
+    }
+}
+
+fn import(state: &mut AppState, kind: ImportKind, file: &str, item: usize) -> Result<String, String> {
+    match kind {
+        ImportKind::Final | ImportKind::Project | ImportKind::Group => {
+            let text = fs::read_to_string(file).map_err(|e| e.to_string())?;
+            let table_type = match kind {
+                ImportKind::Final => TableType::FinalScore,
+                ImportKind::Project => TableType::ProjectScore,
+                _ => TableType::GroupList,
+            };
+            let preview = preview_import(state, table_type, &text);
+            let mut lines = vec![preview.summary()];
+            if !preview.failed.is_empty() {
+                lines.push("以下行解析失败，已跳过:".to_string());
+                lines.extend(preview.failed.iter().map(|e| e.to_string()));
+            }
+            state.apply_import(preview);
+            Ok(lines.join("\n"))
+        }
+        ImportKind::Homework | ImportKind::Discussion => {
+            let target = match kind {
+                ImportKind::Homework => ScoreTarget::Homework,
+                _ => ScoreTarget::Discussion,
+            };
+            let sheet = read_sheet(file)?;
+            Ok(import_item_scores(&mut state.final_table, &sheet, target, item)?.summary())
+        }
+        ImportKind::Report | ImportKind::Pr => {
+            let target = match kind {
+                ImportKind::Report => ProjectScoreTarget::Report,
+                _ => ProjectScoreTarget::Pr,
+            };
+            let sheet = read_sheet(file)?;
+            Ok(import_project_scores(&mut state.project_table, &sheet, target)?.summary())
+        }
+    }
+}
+
+fn compute(state: &mut AppState, sync_project: bool) -> String {
+    state.calculate_project_table();
+    let mut lines = vec![format!("重新计算 {} 个组的 Project 成绩", state.project_table.len())];
+    if sync_project {
+        let report = sync_project_scores(&mut state.final_table, &state.project_table, &state.group_table);
+        lines.push(report.summary());
+    }
+    state.calculate_final_table();
+    lines.push(format!("重新计算 {} 名学生的总评成绩", state.final_table.len()));
+    lines.join("\n")
+}
+
+/// 执行子命令。只有修改数据的导入与统计命令在成功后写回 app_state.json(并记录日志)，
+/// 其余命令只输出结果，不会创建或修改 app_state.json
+pub fn run(command: Command) -> Result<(), String> {
+    let mut state = AppSingleton::load_state().map_err(|e| e.to_string())?;
+    let (info, message, changed) = match command {
+        Command::Import { kind, file, item } => {
+            let message = import(&mut state, kind, &file, item)?;
+            (format!("命令行导入 - {}", file), message, true)
+        }
+        Command::Compute { sync_project } => ("命令行统计分数".to_string(), compute(&mut state, sync_project), true),
+        Command::Export { path } => {
+            export_xlsx(&state, &path).map_err(|e| e.to_string())?;
+            ("命令行导出 Excel".to_string(), format!("已导出到 {}", path), false)
+        }
+        Command::RandomPick { k, file } => {
+            let input_text = read_input(file.as_deref()).map_err(|e| e.to_string())?;
+            let picked = pick_random(&input_text, k);
+            (format!("随机抽取{}个", k), picked.join("\n"), false)
+        }
+        Command::RandomGroup { size, file } => {
+            let input_text = read_input(file.as_deref()).map_err(|e| e.to_string())?;
+            let groups = group_randomly(&input_text, size, state.group_table.len());
+            (format!("随机分组，大小{}", size), groups, false)
+        }
+    };
+    println!("{}", message);
+    if !changed {
+        return Ok(());
+    }
+    state.log(info, message);
+    AppSingleton::save_state(&state).map_err(|e| e.to_string())
+}
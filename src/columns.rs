@@ -0,0 +1,298 @@
+//! 数据表的列模型。
+//!
+//! 表格显示、排序筛选与 Excel 导出都从这里取得表头与单元格。
+//! 分项成绩与组员按数据中最多的一行展开为多列，因此任意数量的分项与任意大小的组都能完整显示。
+
+use std::fmt;
+
+use crate::app_state::{FinalItem, GradingPolicy, GroupItem, ProjectItem, ProjectRules, EXAM_FULL_MARK};
+use crate::score::Score;
+
+/// 单元格的值
+pub enum Cell<'a> {
+    Text(&'a str),
+    Number(f32),
+    /// 带状态的分数，如分项成绩与考试成绩
+    Score(Score),
+    /// 编号，如组号、展示报告 ID
+    Index(usize),
+    /// 该行没有这一列，如分项数量少于其他行
+    Empty,
+}
+
+/// 可编辑的单元格
+pub enum CellMut<'a> {
+    Text(&'a mut String),
+    Number(&'a mut f32),
+    Score(&'a mut Score),
+    Index(&'a mut usize),
+    /// 由其他列计算得到或该行没有这一列
+    ReadOnly,
+}
+
+pub struct TableColumn<K> {
+    pub title: String,
+    pub key: K,
+    /// 列的标识，如 "Midterm"、"HomeworkItem(2)"。不随表头文字与列的位置变化，用于保存排序与筛选
+    pub id: String,
+}
+
+impl<K: fmt::Debug> TableColumn<K> {
+    fn new(title: impl Into<String>, key: K) -> Self {
+        Self {
+            title: title.into(),
+            id: format!("{:?}", key),
+            key,
+        }
+    }
+}
+
+/// 按列显示的数据表行
+pub trait TableModel: Sized {
+    type Key: Copy + fmt::Debug;
+    /// `rows` 的所有列，期末占比、Project 各项满分等表头随 `policy` 与 `rules` 变化
+    fn columns(rows: &[Self], policy: &GradingPolicy, rules: &ProjectRules) -> Vec<TableColumn<Self::Key>>;
+    fn cell(&self, key: Self::Key) -> Cell<'_>;
+    fn cell_mut(&mut self, key: Self::Key) -> CellMut<'_>;
+    /// 参与搜索的文本，如学号与姓名
+    fn search_keys(&self) -> Vec<String>;
+}
+
+/// 分项列：表头名后加序号，如 "讨论分项1"
+fn item_columns<K: fmt::Debug>(title: &str, count: usize, key: fn(usize) -> K) -> Vec<TableColumn<K>> {
+    (0..count).map(|i| TableColumn::new(format!("{}{}", title, i + 1), key(i))).collect()
+}
+
+fn item(values: &[Score], index: usize) -> Cell<'_> {
+    values.get(index).map_or(Cell::Empty, |value| Cell::Score(*value))
+}
+
+fn item_mut(values: &mut [Score], index: usize) -> CellMut<'_> {
+    values.get_mut(index).map_or(CellMut::ReadOnly, CellMut::Score)
+}
+
+/// 带占比的表头，如 "作业(10%)"；占比不确定时只有名称
+fn percent_title(title: &str, percent: Option<f32>) -> String {
+    percent.map_or(title.to_string(), |percent| format!("{}({}%)", title, percent))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum FinalColumn {
+    StudentId,
+    StudentName,
+    FinalScore,
+    GeneralScore,
+    FinalExam,
+    Midterm,
+    Homework,
+    Discussion,
+    Project,
+    Bonus,
+    Adjusted,
+    GradeLevel,
+    GradePoint,
+    DiscussionItem(usize),
+    HomeworkItem(usize),
+}
+
+impl TableModel for FinalItem {
+    type Key = FinalColumn;
+
+    fn columns(rows: &[Self], policy: &GradingPolicy, _rules: &ProjectRules) -> Vec<TableColumn<FinalColumn>> {
+        let discussion_count = rows.iter().map(|row| row.discussion_score.len()).max().unwrap_or(0);
+        let homework_count = rows.iter().map(|row| row.homework_score.len()).max().unwrap_or(0);
+        let mut columns = vec![
+            TableColumn::new("学号", FinalColumn::StudentId),
+            TableColumn::new("姓名", FinalColumn::StudentName),
+            TableColumn::new("总分", FinalColumn::FinalScore),
+            TableColumn::new("平时分", FinalColumn::GeneralScore),
+            TableColumn::new(format!("期末({}%)", policy.final_exam_weight), FinalColumn::FinalExam),
+            TableColumn::new(format!("期中({}%)", EXAM_FULL_MARK / policy.midterm_divisor), FinalColumn::Midterm),
+            TableColumn::new(percent_title("作业", policy.homework_full_mark()), FinalColumn::Homework),
+            TableColumn::new(percent_title("讨论", policy.discussion_full_mark()), FinalColumn::Discussion),
+            TableColumn::new("Project", FinalColumn::Project),
+            TableColumn::new("Bonus", FinalColumn::Bonus),
+        ];
+        let mut reported = vec![
+            TableColumn::new("五级制", FinalColumn::GradeLevel),
+            TableColumn::new("绩点", FinalColumn::GradePoint),
+        ];
+        if rows.iter().any(|row| row.adjusted_score.is_some()) {
+            reported.insert(0, TableColumn::new("调整后总分", FinalColumn::Adjusted));
+        }
+        columns.splice(3..3, reported);
+        columns.extend(item_columns("讨论分项", discussion_count, FinalColumn::DiscussionItem));
+        columns.extend(item_columns("作业分项", homework_count, FinalColumn::HomeworkItem));
+        columns
+    }
+
+    fn cell(&self, key: FinalColumn) -> Cell<'_> {
+        match key {
+            FinalColumn::StudentId => Cell::Text(&self.student_info.student_id),
+            FinalColumn::StudentName => Cell::Text(&self.student_info.student_name),
+            FinalColumn::FinalScore => Cell::Number(self.final_score),
+            FinalColumn::GeneralScore => Cell::Number(self.general_score),
+            FinalColumn::FinalExam => Cell::Score(self.final_exam_score),
+            FinalColumn::Midterm => Cell::Score(self.midterm_score),
+            FinalColumn::Homework => Cell::Number(self.homework_score_sum),
+            FinalColumn::Discussion => Cell::Number(self.discussion_score_sum),
+            FinalColumn::Project => Cell::Number(self.project_score_sum),
+            FinalColumn::Bonus => Cell::Number(self.bonus_score),
+            FinalColumn::Adjusted => self.adjusted_score.map_or(Cell::Empty, Cell::Number),
+            FinalColumn::GradeLevel => Cell::Text(&self.grade_level),
+            FinalColumn::GradePoint => Cell::Number(self.grade_point),
+            FinalColumn::DiscussionItem(i) => item(&self.discussion_score, i),
+            FinalColumn::HomeworkItem(i) => item(&self.homework_score, i),
+        }
+    }
+
+    fn cell_mut(&mut self, key: FinalColumn) -> CellMut<'_> {
+        match key {
+            FinalColumn::StudentId => CellMut::Text(&mut self.student_info.student_id),
+            FinalColumn::StudentName => CellMut::Text(&mut self.student_info.student_name),
+            FinalColumn::FinalExam => CellMut::Score(&mut self.final_exam_score),
+            FinalColumn::Midterm => CellMut::Score(&mut self.midterm_score),
+            FinalColumn::Project => CellMut::Number(&mut self.project_score_sum),
+            FinalColumn::Bonus => CellMut::Number(&mut self.bonus_score),
+            FinalColumn::DiscussionItem(i) => item_mut(&mut self.discussion_score, i),
+            FinalColumn::HomeworkItem(i) => item_mut(&mut self.homework_score, i),
+            FinalColumn::FinalScore
+            | FinalColumn::Adjusted
+            | FinalColumn::GradeLevel
+            | FinalColumn::GradePoint
+            | FinalColumn::GeneralScore
+            | FinalColumn::Homework
+            | FinalColumn::Discussion => CellMut::ReadOnly,
+        }
+    }
+
+    fn search_keys(&self) -> Vec<String> {
+        vec![self.student_info.student_id.clone(), self.student_info.student_name.clone()]
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ProjectColumn {
+    GroupId,
+    Total,
+    Presentation,
+    Bonus,
+    ReportSum,
+    PrSum,
+    PresentationId,
+    Report(usize),
+    Pr(usize),
+}
+
+impl TableModel for ProjectItem {
+    type Key = ProjectColumn;
+
+    fn columns(rows: &[Self], _policy: &GradingPolicy, rules: &ProjectRules) -> Vec<TableColumn<ProjectColumn>> {
+        let report_count = rows.iter().map(|row| row.report_score.len()).max().unwrap_or(0);
+        let pr_count = rows.iter().map(|row| row.pr_score.len()).max().unwrap_or(0);
+        let mut columns = vec![
+            TableColumn::new("组号", ProjectColumn::GroupId),
+            TableColumn::new(format!("Project 总分({})", rules.total_full_mark()), ProjectColumn::Total),
+            TableColumn::new(format!("展示分数({})", rules.presentation_full_mark), ProjectColumn::Presentation),
+            TableColumn::new("Bonus 分数", ProjectColumn::Bonus),
+            TableColumn::new(format!("Report 总分({})", rules.report_total_full_mark()), ProjectColumn::ReportSum),
+            TableColumn::new(format!("PR 总分({})", rules.pr_total_full_mark()), ProjectColumn::PrSum),
+            TableColumn::new("展示报告 ID", ProjectColumn::PresentationId),
+        ];
+        let report_title = format!("Report({})", rules.report_full_mark);
+        columns.extend(item_columns(&report_title, report_count, ProjectColumn::Report));
+        columns.extend(item_columns(&format!("PR({})", rules.pr_full_mark), pr_count, ProjectColumn::Pr));
+        columns
+    }
+
+    fn cell(&self, key: ProjectColumn) -> Cell<'_> {
+        match key {
+            ProjectColumn::GroupId => Cell::Index(self.group_id),
+            ProjectColumn::Total => Cell::Number(self.total_score),
+            ProjectColumn::Presentation => Cell::Number(self.presentation_score),
+            ProjectColumn::Bonus => Cell::Number(self.bonus_score),
+            ProjectColumn::ReportSum => Cell::Number(self.report_score_sum),
+            ProjectColumn::PrSum => Cell::Number(self.pr_score_sum),
+            ProjectColumn::PresentationId => Cell::Index(self.presentation_id),
+            ProjectColumn::Report(i) => item(&self.report_score, i),
+            ProjectColumn::Pr(i) => item(&self.pr_score, i),
+        }
+    }
+
+    fn cell_mut(&mut self, key: ProjectColumn) -> CellMut<'_> {
+        match key {
+            ProjectColumn::GroupId => CellMut::Index(&mut self.group_id),
+            ProjectColumn::Presentation => CellMut::Number(&mut self.presentation_score),
+            ProjectColumn::PresentationId => CellMut::Index(&mut self.presentation_id),
+            ProjectColumn::Report(i) => item_mut(&mut self.report_score, i),
+            ProjectColumn::Pr(i) => item_mut(&mut self.pr_score, i),
+            ProjectColumn::Total | ProjectColumn::Bonus | ProjectColumn::ReportSum | ProjectColumn::PrSum => {
+                CellMut::ReadOnly
+            }
+        }
+    }
+
+    fn search_keys(&self) -> Vec<String> {
+        vec![self.group_id.to_string()]
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum GroupColumn {
+    GroupId,
+    MemberId(usize),
+    MemberName(usize),
+}
+
+impl TableModel for GroupItem {
+    type Key = GroupColumn;
+
+    /// 第一位成员为组长，其余为组员
+    fn columns(rows: &[Self], _policy: &GradingPolicy, _rules: &ProjectRules) -> Vec<TableColumn<GroupColumn>> {
+        let member_count = rows.iter().map(|row| row.group_members.len()).max().unwrap_or(0);
+        let mut columns = vec![TableColumn::new("组号", GroupColumn::GroupId)];
+        for i in 0..member_count {
+            let role = if i == 0 { "组长" } else { "组员" };
+            columns.push(TableColumn::new(format!("{}学号", role), GroupColumn::MemberId(i)));
+            columns.push(TableColumn::new(format!("{}姓名", role), GroupColumn::MemberName(i)));
+        }
+        columns
+    }
+
+    fn cell(&self, key: GroupColumn) -> Cell<'_> {
+        match key {
+            GroupColumn::GroupId => Cell::Index(self.group_id),
+            GroupColumn::MemberId(i) => self
+                .group_members
+                .get(i)
+                .map_or(Cell::Empty, |member| Cell::Text(&member.student_id)),
+            GroupColumn::MemberName(i) => self
+                .group_members
+                .get(i)
+                .map_or(Cell::Empty, |member| Cell::Text(&member.student_name)),
+        }
+    }
+
+    fn cell_mut(&mut self, key: GroupColumn) -> CellMut<'_> {
+        match key {
+            GroupColumn::GroupId => CellMut::Index(&mut self.group_id),
+            GroupColumn::MemberId(i) => self
+                .group_members
+                .get_mut(i)
+                .map_or(CellMut::ReadOnly, |member| CellMut::Text(&mut member.student_id)),
+            GroupColumn::MemberName(i) => self
+                .group_members
+                .get_mut(i)
+                .map_or(CellMut::ReadOnly, |member| CellMut::Text(&mut member.student_name)),
+        }
+    }
+
+    fn search_keys(&self) -> Vec<String> {
+        let mut keys = vec![self.group_id.to_string()];
+        for member in &self.group_members {
+            keys.push(member.student_id.clone());
+            keys.push(member.student_name.clone());
+        }
+        keys
+    }
+}
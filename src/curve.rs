@@ -0,0 +1,332 @@
+//! 总评成绩的等级分布检查与调分。
+//!
+//! 学校限制优秀率上限并要求及格率下限。`CurvePolicy` 记录这两个比例与已应用的调分曲线；
+//! `propose` 按当前成绩求出满足要求、且对原始分数改动最小的曲线参数。
+//! 调分结果写入 `FinalItem::adjusted_score`，原始总分保持不变。
+//! 期末考试低于及格线或缺考、总分记为 0 的学生不参与调分。
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::{AppState, FinalItem, GradingPolicy};
+use crate::grade::{Distribution, GradeBand, EXCELLENT_LINE, PASS_LINE};
+
+/// 调分结果保留的小数位数对应的倍数
+const ROUNDING: f32 = 100.0;
+/// 刚好低于某条线时与该线的距离
+const MARGIN: f32 = 0.01;
+
+/// 调分曲线，输入与输出都是 0–100 的总分
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Curve {
+    /// 所有人加 `shift` 分，可以为负
+    Linear { shift: f32 },
+    /// 100 × (x / 100)^exponent，exponent 为 0.5 时即开方乘十
+    Power { exponent: f32 },
+    /// 折线经过 (0, 0)、(pass_from, 60)、(excellent_from, 85)、(100, 100)
+    Piecewise { pass_from: f32, excellent_from: f32 },
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve::Linear { shift: 0.0 }
+    }
+}
+
+impl Curve {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Curve::Linear { .. } => "线性平移",
+            Curve::Power { .. } => "开方(幂函数)",
+            Curve::Piecewise { .. } => "分段映射",
+        }
+    }
+
+    /// 调分后的分数，限制在 0–100 并保留两位小数
+    pub fn apply(&self, score: f32) -> f32 {
+        let adjusted = match *self {
+            Curve::Linear { shift } => score + shift,
+            Curve::Power { exponent } => 100.0 * (score.max(0.0) / 100.0).powf(exponent),
+            Curve::Piecewise {
+                pass_from,
+                excellent_from,
+            } => {
+                if score < pass_from {
+                    score / pass_from * PASS_LINE
+                } else if score < excellent_from {
+                    PASS_LINE + (score - pass_from) / (excellent_from - pass_from) * (EXCELLENT_LINE - PASS_LINE)
+                } else if excellent_from < 100.0 {
+                    EXCELLENT_LINE + (score - excellent_from) / (100.0 - excellent_from) * (100.0 - EXCELLENT_LINE)
+                } else {
+                    EXCELLENT_LINE
+                }
+            }
+        };
+        (adjusted.clamp(0.0, 100.0) * ROUNDING).round() / ROUNDING
+    }
+
+    /// 曲线参数是否有效，如分段映射的两个转折点需递增
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Curve::Linear { shift } => shift.is_finite(),
+            Curve::Power { exponent } => exponent.is_finite() && exponent > 0.0,
+            Curve::Piecewise {
+                pass_from,
+                excellent_from,
+            } => 0.0 < pass_from && pass_from < excellent_from && excellent_from <= 100.0,
+        }
+    }
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Curve::Linear { shift } => write!(f, "{} {:+.2} 分", self.label(), shift),
+            Curve::Power { exponent } => write!(f, "{} 指数 {:.3}", self.label(), exponent),
+            Curve::Piecewise {
+                pass_from,
+                excellent_from,
+            } => write!(
+                f,
+                "{} {:.2} 分及格、{:.2} 分优秀",
+                self.label(),
+                pass_from,
+                excellent_from
+            ),
+        }
+    }
+}
+
+/// 等级分布要求与已应用的调分曲线
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct CurvePolicy {
+    /// 优秀人数占比上限(0 到 1)
+    pub max_excellent_rate: f32,
+    /// 及格人数占比下限(0 到 1)
+    pub min_pass_rate: f32,
+    /// 已应用的曲线，None 表示未调分
+    pub applied: Option<Curve>,
+    /// 正在预览的曲线
+    pub draft: Curve,
+}
+
+impl Default for CurvePolicy {
+    fn default() -> Self {
+        Self {
+            max_excellent_rate: 0.4,
+            min_pass_rate: 0.8,
+            applied: None,
+            draft: Curve::default(),
+        }
+    }
+}
+
+/// 是否参与调分：期末考试未达到及格线或没有分数、总分记为 0 的学生不参与
+fn is_curved(row: &FinalItem, policy: &GradingPolicy) -> bool {
+    row.final_exam_score.value().is_some_and(|score| score >= policy.final_exam_threshold)
+}
+
+/// 按 `curve` 调分后的总分
+pub fn adjusted(row: &FinalItem, policy: &GradingPolicy, curve: &Curve) -> f32 {
+    if is_curved(row, policy) {
+        curve.apply(row.final_score)
+    } else {
+        row.final_score
+    }
+}
+
+/// 分布是否满足要求，返回未满足的条目
+pub fn check(distribution: &Distribution, quota: &CurvePolicy) -> Vec<String> {
+    let mut problems = vec![];
+    let excellent = distribution.rate(GradeBand::Excellent);
+    if excellent > quota.max_excellent_rate {
+        problems.push(format!(
+            "优秀率 {:.1}% 超过上限 {:.1}%",
+            excellent * 100.0,
+            quota.max_excellent_rate * 100.0
+        ));
+    }
+    let pass = distribution.pass_rate();
+    if pass < quota.min_pass_rate {
+        problems.push(format!(
+            "及格率 {:.1}% 低于下限 {:.1}%",
+            pass * 100.0,
+            quota.min_pass_rate * 100.0
+        ));
+    }
+    problems
+}
+
+/// 求出满足要求所需的原始分数界限：
+/// 原始分不低于第一个值的学生需要及格，原始分不高于第二个值的学生不能优秀。
+/// 不需要限制时对应的值为 None
+fn thresholds(rows: &[FinalItem], policy: &GradingPolicy, quota: &CurvePolicy) -> (Option<f32>, Option<f32>) {
+    let total = rows.len();
+    let mut scores: Vec<f32> = rows
+        .iter()
+        .filter(|row| is_curved(row, policy))
+        .map(|row| row.final_score)
+        .collect();
+    scores.sort_by(|a, b| b.total_cmp(a));
+    // 至少需要及格的人数，至多允许优秀的人数
+    let pass_needed = (quota.min_pass_rate * total as f32 - 1e-4).ceil().max(0.0) as usize;
+    let excellent_allowed = (quota.max_excellent_rate * total as f32 + 1e-4).floor() as usize;
+    let pass_from = match pass_needed {
+        0 => None,
+        n => scores.get(n - 1).or(scores.last()).copied(),
+    };
+    let excellent_below = scores.get(excellent_allowed).copied();
+    (pass_from, excellent_below)
+}
+
+/// 在 [lower, upper] 中取最接近 `identity` 的值；区间为空时优先满足及格率，取及格率对应的界限 `pass_bound`
+fn closest(identity: f32, lower: f32, upper: f32, pass_bound: f32) -> f32 {
+    if lower <= upper {
+        identity.clamp(lower, upper)
+    } else {
+        pass_bound
+    }
+}
+
+/// 按 `kind` 的曲线类型，求出满足要求且对原始分数改动最小的参数。
+/// 要求无法同时满足时优先满足及格率，可用 `check` 检查结果
+pub fn propose(state: &AppState, kind: &Curve) -> Curve {
+    let policy = &state.grading_policy;
+    let (pass_from, excellent_below) = thresholds(&state.final_table, policy, &state.curve);
+    match kind {
+        Curve::Linear { .. } => {
+            let lower = pass_from.map_or(f32::MIN, |score| PASS_LINE - score);
+            let upper = excellent_below.map_or(f32::MAX, |score| EXCELLENT_LINE - MARGIN - score);
+            Curve::Linear {
+                shift: closest(0.0, lower, upper, lower),
+            }
+        }
+        Curve::Power { .. } => {
+            // 100 × (x / 100)^p 随 p 减小而增大，分数为 0 或 100 时不受 p 影响
+            let upper = pass_from
+                .filter(|score| *score > 0.0 && *score < 100.0)
+                .map_or(f32::MAX, |score| (PASS_LINE / 100.0).ln() / (score / 100.0).ln());
+            let lower = excellent_below
+                .filter(|score| *score > 0.0 && *score < 100.0)
+                .map_or(0.01, |score| ((EXCELLENT_LINE - MARGIN) / 100.0).ln() / (score / 100.0).ln());
+            Curve::Power {
+                exponent: closest(1.0, lower, upper, upper),
+            }
+        }
+        Curve::Piecewise { .. } => {
+            let pass_from = pass_from.map_or(PASS_LINE, |score| score.clamp(MARGIN, PASS_LINE));
+            let excellent_from = excellent_below
+                .map_or(EXCELLENT_LINE, |score| (score + MARGIN).max(EXCELLENT_LINE))
+                .clamp(pass_from + MARGIN, 100.0);
+            Curve::Piecewise {
+                pass_from,
+                excellent_from,
+            }
+        }
+    }
+}
+
+impl AppState {
+    /// 总评的等级分布，已调分时按调整后总分统计
+    pub fn grade_distribution(&self) -> Distribution {
+        Distribution::of(self.final_table.iter().map(FinalItem::reported_score))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::Score;
+
+    /// 期末均为 80 分、总分依次为 `scores` 的总评表
+    fn state_with(scores: &[f32]) -> AppState {
+        AppState {
+            final_table: scores
+                .iter()
+                .map(|&final_score| FinalItem {
+                    final_score,
+                    final_exam_score: Score::Scored(80.0),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn problems(state: &AppState, curve: &Curve) -> Vec<String> {
+        let policy = &state.grading_policy;
+        let scores = state.final_table.iter().map(|row| adjusted(row, policy, curve));
+        check(&Distribution::of(scores), &state.curve)
+    }
+
+    #[test]
+    fn thresholds_follow_the_quota() {
+        let state = state_with(&[40.0, 45.0, 50.0, 55.0, 60.0, 65.0, 70.0, 75.0, 80.0, 90.0]);
+        // 及格率 80% 需要前 8 人及格，优秀率 40% 允许前 4 人优秀
+        let (pass_from, excellent_below) = thresholds(&state.final_table, &state.grading_policy, &state.curve);
+        assert_eq!(pass_from, Some(50.0));
+        assert_eq!(excellent_below, Some(65.0));
+    }
+
+    #[test]
+    fn thresholds_count_uncurved_students_in_the_total() {
+        let mut state = state_with(&[50.0, 60.0, 70.0, 80.0, 90.0]);
+        // 期末低于 40 分的学生不参与调分，但仍计入总人数
+        state.final_table[0].final_exam_score = Score::Scored(30.0);
+        let (pass_from, _) = thresholds(&state.final_table, &state.grading_policy, &state.curve);
+        assert_eq!(pass_from, Some(60.0));
+    }
+
+    #[test]
+    fn proposed_curves_satisfy_the_quota() {
+        let state = state_with(&[40.0, 45.0, 50.0, 55.0, 60.0, 65.0, 70.0, 75.0, 80.0, 90.0]);
+        assert!(!problems(&state, &Curve::default()).is_empty());
+        let kinds = [
+            Curve::Linear { shift: 0.0 },
+            Curve::Power { exponent: 1.0 },
+            Curve::Piecewise {
+                pass_from: PASS_LINE,
+                excellent_from: EXCELLENT_LINE,
+            },
+        ];
+        for kind in kinds {
+            let curve = propose(&state, &kind);
+            assert!(curve.is_valid(), "{}", curve);
+            assert!(problems(&state, &curve).is_empty(), "{}", curve);
+        }
+        assert_eq!(propose(&state, &kinds[0]), Curve::Linear { shift: 10.0 });
+    }
+
+    #[test]
+    fn propose_keeps_scores_that_already_satisfy_the_quota() {
+        let state = state_with(&[50.0, 55.0, 60.0, 65.0, 70.0, 75.0, 80.0, 86.0, 90.0, 95.0]);
+        assert!(problems(&state, &Curve::default()).is_empty());
+        assert_eq!(propose(&state, &Curve::default()), Curve::Linear { shift: 0.0 });
+        assert_eq!(propose(&state, &Curve::Power { exponent: 0.5 }), Curve::Power { exponent: 1.0 });
+    }
+
+    #[test]
+    fn closest_prefers_the_pass_bound_when_the_range_is_empty() {
+        assert_eq!(closest(0.0, -5.0, 5.0, -5.0), 0.0);
+        assert_eq!(closest(0.0, 2.0, 5.0, 2.0), 2.0);
+        assert_eq!(closest(0.0, 8.0, 5.0, 8.0), 8.0);
+    }
+
+    #[test]
+    fn apply_clamps_and_rounds() {
+        assert_eq!(Curve::Linear { shift: 10.0 }.apply(95.0), 100.0);
+        assert_eq!(Curve::Linear { shift: -10.0 }.apply(5.0), 0.0);
+        assert_eq!(Curve::Linear { shift: 0.004 }.apply(59.996), 60.0);
+        assert_eq!(Curve::Power { exponent: 0.5 }.apply(36.0), 60.0);
+        let piecewise = Curve::Piecewise {
+            pass_from: 50.0,
+            excellent_from: 80.0,
+        };
+        assert_eq!(piecewise.apply(25.0), 30.0);
+        assert_eq!(piecewise.apply(50.0), PASS_LINE);
+        assert_eq!(piecewise.apply(80.0), EXCELLENT_LINE);
+        assert_eq!(piecewise.apply(100.0), 100.0);
+    }
+}
@@ -0,0 +1,222 @@
+//! 作业截止时间与迟交扣分。
+//!
+//! 截止时间与提交时间以 UTC 保存，输入与显示时按课程所在的 Asia/Shanghai 时区换算。
+//! 超过截止时间且超出宽限期的提交按迟交天数扣分：每迟交一天(不足一天按一天)扣该次分数的
+//! `percent_per_day`%，最多扣 `max_percent`%。扣分在计算作业分之前作用于分项成绩。
+
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::{Asia::Shanghai, Tz};
+use serde::{Deserialize, Serialize};
+
+use crate::assignment::AssignmentPool;
+use crate::score::Score;
+
+/// 课程所在时区
+pub const COURSE_TZ: Tz = Shanghai;
+
+/// 没有时区的时间可以使用的格式
+const TIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+
+/// 解析时间：带时区的 RFC 3339 时间按其时区，其余按课程时区。
+/// Excel 中的日期单元格在读取表格时已转换为时间文本，单独的数字不视为时间
+pub fn parse_time(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Utc));
+    }
+    let local = TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())?;
+    COURSE_TZ
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// 按课程时区显示时间，如 "2024-03-10 23:59"
+pub fn format_time(time: &DateTime<Utc>) -> String {
+    time.with_timezone(&COURSE_TZ).format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// 迟交扣分规则
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct LatePolicy {
+    /// 每迟交一天扣除该次分数的百分比
+    pub percent_per_day: f32,
+    /// 最多扣除的百分比
+    pub max_percent: f32,
+    /// 截止后多少分钟内提交不算迟交
+    pub grace_minutes: u32,
+}
+
+impl Default for LatePolicy {
+    fn default() -> Self {
+        Self {
+            percent_per_day: 10.0,
+            max_percent: 50.0,
+            grace_minutes: 0,
+        }
+    }
+}
+
+impl LatePolicy {
+    /// 迟交天数，未迟交或在宽限期内时为 0
+    pub fn days_late(&self, deadline: &DateTime<Utc>, submitted: &DateTime<Utc>) -> u32 {
+        let late = *submitted - *deadline;
+        if late <= Duration::minutes(self.grace_minutes as i64) {
+            return 0;
+        }
+        let day = Duration::days(1).num_seconds();
+        (late.num_seconds() + day - 1).div_euclid(day) as u32
+    }
+
+    /// 迟交 `days` 天扣除的百分比，在 0 到 100 之间。设置中的负数按 0 处理，不会扣成负分
+    pub fn percent(&self, days: u32) -> f32 {
+        let cap = self.max_percent.clamp(0.0, 100.0);
+        (days as f32 * self.percent_per_day).max(0.0).min(cap)
+    }
+}
+
+/// 一次迟交扣分
+#[derive(Clone, PartialEq, Debug)]
+pub struct LateDeduction {
+    /// 分项序号
+    pub index: usize,
+    pub name: String,
+    pub deadline: DateTime<Utc>,
+    pub submitted: DateTime<Utc>,
+    pub days: u32,
+    /// 扣除的百分比
+    pub percent: f32,
+    /// 扣除的分数
+    pub points: f32,
+}
+
+/// 有截止时间与提交时间、且迟交的分项对应的扣分；没有分数的分项不扣分
+pub fn late_deductions(
+    scores: &[Score],
+    submitted: &[Option<DateTime<Utc>>],
+    pool: &AssignmentPool,
+    policy: &LatePolicy,
+) -> Vec<LateDeduction> {
+    scores
+        .iter()
+        .zip(submitted)
+        .zip(&pool.assignments)
+        .enumerate()
+        .filter_map(|(index, ((score, submitted), assignment))| {
+            let (value, submitted, deadline) = (score.value()?, (*submitted)?, assignment.deadline?);
+            let days = policy.days_late(&deadline, &submitted);
+            let percent = policy.percent(days);
+            (days > 0).then(|| LateDeduction {
+                index,
+                name: assignment.name.clone(),
+                deadline,
+                submitted,
+                days,
+                percent,
+                points: value * percent / 100.0,
+            })
+        })
+        .collect()
+}
+
+/// 扣除迟交分数后的分项成绩
+pub fn apply_deductions(scores: &[Score], deductions: &[LateDeduction]) -> Vec<Score> {
+    let mut scores = scores.to_vec();
+    for deduction in deductions {
+        if let Some(Score::Scored(value)) = scores.get_mut(deduction.index) {
+            *value -= deduction.points;
+        }
+    }
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assignment::Assignment;
+
+    fn time(text: &str) -> DateTime<Utc> {
+        parse_time(text).unwrap()
+    }
+
+    #[test]
+    fn parse_time_uses_the_course_time_zone() {
+        assert_eq!(time("2024-03-10 23:59"), time("2024-03-10T15:59:00Z"));
+        assert_eq!(time("2024/03/10 23:59:30"), time("2024-03-10T23:59:30+08:00"));
+        assert_eq!(format_time(&time("2024-03-10T15:59:00Z")), "2024-03-10 23:59");
+        for text in ["", "45372.5", "2024-13-01 00:00", "明天"] {
+            assert!(parse_time(text).is_none(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn days_late_rounds_up_after_the_grace_period() {
+        let policy = LatePolicy {
+            grace_minutes: 30,
+            ..LatePolicy::default()
+        };
+        let deadline = time("2024-03-10 23:59");
+        assert_eq!(policy.days_late(&deadline, &time("2024-03-10 12:00")), 0);
+        assert_eq!(policy.days_late(&deadline, &time("2024-03-11 00:29")), 0);
+        assert_eq!(policy.days_late(&deadline, &time("2024-03-11 00:30")), 1);
+        assert_eq!(policy.days_late(&deadline, &time("2024-03-11 23:59")), 1);
+        assert_eq!(policy.days_late(&deadline, &time("2024-03-12 00:00")), 2);
+    }
+
+    #[test]
+    fn percent_is_capped() {
+        let policy = LatePolicy::default();
+        assert_eq!(policy.percent(0), 0.0);
+        assert_eq!(policy.percent(3), 30.0);
+        assert_eq!(policy.percent(9), 50.0);
+    }
+
+    #[test]
+    fn percent_stays_within_zero_and_one_hundred() {
+        let negative_cap = LatePolicy { max_percent: -10.0, ..Default::default() };
+        assert_eq!(negative_cap.percent(3), 0.0);
+        let negative_rate = LatePolicy { percent_per_day: -10.0, ..Default::default() };
+        assert_eq!(negative_rate.percent(3), 0.0);
+        let over_cap = LatePolicy { percent_per_day: 60.0, max_percent: 150.0, ..Default::default() };
+        assert_eq!(over_cap.percent(3), 100.0);
+    }
+
+    #[test]
+    fn deductions_do_not_depend_on_weighting() {
+        let pool = AssignmentPool {
+            assignments: vec![
+                Assignment {
+                    deadline: Some(time("2024-03-10 23:59")),
+                    ..Assignment::default()
+                },
+                Assignment::default(),
+                Assignment {
+                    deadline: Some(time("2024-03-17 23:59")),
+                    ..Assignment::default()
+                },
+            ],
+            weighted: false,
+            ..AssignmentPool::default()
+        };
+        let scores = [Score::Scored(10.0), Score::Scored(10.0), Score::Missing];
+        let submitted = [Some(time("2024-03-12 08:00")), Some(time("2024-04-01 00:00")), Some(time("2024-03-20 00:00"))];
+        // 第 2 项没有截止时间，第 3 项没有分数
+        let deductions = late_deductions(&scores, &submitted, &pool, &LatePolicy::default());
+        assert_eq!(deductions.len(), 1);
+        assert_eq!((deductions[0].index, deductions[0].days, deductions[0].points), (0, 2, 2.0));
+        assert_eq!(
+            apply_deductions(&scores, &deductions),
+            vec![Score::Scored(8.0), Score::Scored(10.0), Score::Missing]
+        );
+    }
+}
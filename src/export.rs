@@ -0,0 +1,52 @@
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+
+use crate::app_state::*;
+use crate::columns::{Cell, TableModel};
+use crate::score::Score;
+
+pub const DEFAULT_EXPORT_PATH: &str = "ads_scores.xlsx";
+
+/// 将总评、Project、组队名单三张表写入同一个 Excel 文件，每张表一个 sheet
+pub fn export_xlsx(state: &AppState, path: &str) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+
+    let sheet = workbook.add_worksheet().set_name("总评成绩")?;
+    write_sheet(sheet, &state.final_table, state, &header_format)?;
+
+    let sheet = workbook.add_worksheet().set_name("Project 成绩")?;
+    write_sheet(sheet, &state.project_table, state, &header_format)?;
+
+    let sheet = workbook.add_worksheet().set_name("组队名单")?;
+    write_sheet(sheet, &state.group_table, state, &header_format)?;
+
+    workbook.save(path)
+}
+
+/// 按列模型写入一张表：第一行为表头，文本与分数的状态写为字符串，分数与编号写为数字
+fn write_sheet<T: TableModel>(
+    sheet: &mut Worksheet,
+    rows: &[T],
+    state: &AppState,
+    format: &Format,
+) -> Result<(), XlsxError> {
+    let columns = T::columns(rows, &state.grading_policy, &state.project_rules);
+    for (col, column) in columns.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, &column.title, format)?;
+    }
+    for (index, item) in rows.iter().enumerate() {
+        let row = index as u32 + 1;
+        for (col, column) in columns.iter().enumerate() {
+            let col = col as u16;
+            match item.cell(column.key) {
+                Cell::Text(text) => sheet.write_string(row, col, text)?,
+                Cell::Number(value) => sheet.write_number(row, col, value as f64)?,
+                Cell::Index(value) => sheet.write_number(row, col, value as f64)?,
+                Cell::Score(Score::Scored(value)) => sheet.write_number(row, col, value as f64)?,
+                Cell::Score(status) => sheet.write_string(row, col, status.label())?,
+                Cell::Empty => continue,
+            };
+        }
+    }
+    Ok(())
+}
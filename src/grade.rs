@@ -0,0 +1,299 @@
+//! 百分制成绩对应的五级制等级与绩点。
+//!
+//! 分数段按浙江大学的百分制与五级制对照：优秀 85–100，良好 75–84，中等 65–74，及格 60–64，不及格 60 以下。
+//! `GradeBand` 固定为上述分数段，用于调分时检查优秀率与及格率；
+//! 向教务系统报送的五级制与绩点由可配置的 `GradeScale` 换算，默认为浙江大学的 5 分制绩点。
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::{AppState, FinalItem, GradingPolicy};
+use crate::curve::{adjusted, Curve};
+
+/// 五级制等级，按从高到低排列
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GradeBand {
+    Excellent,
+    Good,
+    Medium,
+    Pass,
+    Fail,
+}
+
+/// 及格线
+pub const PASS_LINE: f32 = 60.0;
+/// 优秀线
+pub const EXCELLENT_LINE: f32 = 85.0;
+
+impl GradeBand {
+    pub const ALL: [GradeBand; 5] = [
+        GradeBand::Excellent,
+        GradeBand::Good,
+        GradeBand::Medium,
+        GradeBand::Pass,
+        GradeBand::Fail,
+    ];
+
+    pub fn of(score: f32) -> Self {
+        match score {
+            s if s >= EXCELLENT_LINE => GradeBand::Excellent,
+            s if s >= 75.0 => GradeBand::Good,
+            s if s >= 65.0 => GradeBand::Medium,
+            s if s >= PASS_LINE => GradeBand::Pass,
+            _ => GradeBand::Fail,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GradeBand::Excellent => "优秀",
+            GradeBand::Good => "良好",
+            GradeBand::Medium => "中等",
+            GradeBand::Pass => "及格",
+            GradeBand::Fail => "不及格",
+        }
+    }
+
+    /// 分数段，如 "85–100"
+    pub fn range_label(&self) -> &'static str {
+        match self {
+            GradeBand::Excellent => "85–100",
+            GradeBand::Good => "75–84",
+            GradeBand::Medium => "65–74",
+            GradeBand::Pass => "60–64",
+            GradeBand::Fail => "<60",
+        }
+    }
+
+    /// 该等级的最低分
+    pub fn min_score(&self) -> f32 {
+        match self {
+            GradeBand::Excellent => EXCELLENT_LINE,
+            GradeBand::Good => 75.0,
+            GradeBand::Medium => 65.0,
+            GradeBand::Pass => PASS_LINE,
+            GradeBand::Fail => 0.0,
+        }
+    }
+
+    fn index(&self) -> usize {
+        GradeBand::ALL.iter().position(|band| band == self).unwrap_or(0)
+    }
+}
+
+/// 各等级的人数
+#[derive(Clone, Default, PartialEq)]
+pub struct Distribution {
+    counts: [usize; 5],
+}
+
+impl Distribution {
+    pub fn of(scores: impl IntoIterator<Item = f32>) -> Self {
+        let mut distribution = Self::default();
+        for score in scores {
+            distribution.counts[GradeBand::of(score).index()] += 1;
+        }
+        distribution
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    pub fn count(&self, band: GradeBand) -> usize {
+        self.counts[band.index()]
+    }
+
+    /// `band` 的人数占比(0 到 1)，没有数据时为 0
+    pub fn rate(&self, band: GradeBand) -> f32 {
+        match self.total() {
+            0 => 0.0,
+            total => self.count(band) as f32 / total as f32,
+        }
+    }
+
+    /// 及格(含)以上的人数占比
+    pub fn pass_rate(&self) -> f32 {
+        1.0 - self.rate(GradeBand::Fail)
+    }
+}
+
+/// 五级制等级的换算规则：不低于 `min_score` 的成绩记为 `name`
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct GradeLevel {
+    pub min_score: f32,
+    pub name: String,
+}
+
+/// 绩点的换算规则：不低于 `min_score` 的成绩记为 `point`
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct GradePoint {
+    pub min_score: f32,
+    pub point: f32,
+}
+
+/// 百分制到五级制与绩点的换算规则
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct GradeScale {
+    pub levels: Vec<GradeLevel>,
+    pub points: Vec<GradePoint>,
+}
+
+/// 浙江大学 5 分制绩点：(最低分, 绩点)
+const ZJU_POINTS: [(f32, f32); 14] = [
+    (95.0, 5.0),
+    (92.0, 4.8),
+    (89.0, 4.5),
+    (86.0, 4.2),
+    (83.0, 3.9),
+    (80.0, 3.6),
+    (77.0, 3.3),
+    (74.0, 3.0),
+    (71.0, 2.7),
+    (68.0, 2.4),
+    (65.0, 2.1),
+    (62.0, 1.8),
+    (60.0, 1.5),
+    (0.0, 0.0),
+];
+
+impl Default for GradeScale {
+    fn default() -> Self {
+        Self {
+            levels: GradeBand::ALL
+                .iter()
+                .map(|band| GradeLevel {
+                    min_score: band.min_score(),
+                    name: band.label().to_string(),
+                })
+                .collect(),
+            points: ZJU_POINTS
+                .iter()
+                .map(|&(min_score, point)| GradePoint { min_score, point })
+                .collect(),
+        }
+    }
+}
+
+impl GradeScale {
+    /// `score` 对应的五级制等级，低于所有规则时为空
+    pub fn level(&self, score: f32) -> String {
+        self.levels
+            .iter()
+            .filter(|level| score >= level.min_score)
+            .max_by(|a, b| a.min_score.total_cmp(&b.min_score))
+            .map(|level| level.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// `score` 对应的绩点，低于所有规则时为 0
+    pub fn point(&self, score: f32) -> f32 {
+        self.points
+            .iter()
+            .filter(|point| score >= point.min_score)
+            .max_by(|a, b| a.min_score.total_cmp(&b.min_score))
+            .map_or(0.0, |point| point.point)
+    }
+
+    /// 按最低分从高到低排列规则
+    pub fn sort(&mut self) {
+        self.levels.sort_by(|a, b| b.min_score.total_cmp(&a.min_score));
+        self.points.sort_by(|a, b| b.min_score.total_cmp(&a.min_score));
+    }
+}
+
+impl FinalItem {
+    /// 报送的百分制成绩：已调分时为调整后总分，否则为总分
+    pub fn reported_score(&self) -> f32 {
+        self.adjusted_score.unwrap_or(self.final_score)
+    }
+
+    /// 按调分曲线与换算规则更新调整后总分、五级制等级与绩点，期末没有分数时等级记为缺考等标记
+    pub fn update_reported(&mut self, policy: &GradingPolicy, curve: Option<Curve>, scale: &GradeScale) {
+        self.adjusted_score = curve.map(|curve| adjusted(self, policy, &curve));
+        let score = self.reported_score();
+        // 缺考、缓考等与真实的 0 分分开标出
+        self.grade_level = match self.exam_flag() {
+            Some(flag) => flag.to_string(),
+            None => scale.level(score),
+        };
+        self.grade_point = scale.point(score);
+    }
+}
+
+impl AppState {
+    /// 按已应用的调分曲线与换算规则更新总评表的调整后总分、五级制等级与绩点
+    pub fn update_reported_scores(&mut self) {
+        let (policy, curve, scale) = (&self.grading_policy, self.curve.applied, &self.grade_scale);
+        for row in self.final_table.iter_mut() {
+            row.update_reported(policy, curve, scale);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_scale_follows_the_band_boundaries() {
+        let scale = GradeScale::default();
+        assert_eq!(scale.level(100.0), "优秀");
+        assert_eq!(scale.level(85.0), "优秀");
+        assert_eq!(scale.level(84.99), "良好");
+        assert_eq!(scale.level(65.0), "中等");
+        assert_eq!(scale.level(60.0), "及格");
+        assert_eq!(scale.level(59.99), "不及格");
+        assert_eq!(scale.level(0.0), "不及格");
+    }
+
+    #[test]
+    fn default_scale_uses_the_five_point_table() {
+        let scale = GradeScale::default();
+        assert_eq!(scale.point(95.0), 5.0);
+        assert_eq!(scale.point(94.99), 4.8);
+        assert_eq!(scale.point(86.0), 4.2);
+        assert_eq!(scale.point(60.0), 1.5);
+        assert_eq!(scale.point(59.0), 0.0);
+    }
+
+    #[test]
+    fn scale_does_not_depend_on_rule_order() {
+        let mut scale = GradeScale::default();
+        scale.levels.reverse();
+        scale.points.reverse();
+        assert_eq!(scale.level(90.0), "优秀");
+        assert_eq!(scale.point(90.0), 4.5);
+        scale.sort();
+        assert_eq!(scale.levels[0].min_score, EXCELLENT_LINE);
+        assert_eq!(scale.points.last().map(|point| point.min_score), Some(0.0));
+    }
+
+    #[test]
+    fn scores_below_every_rule_have_no_level() {
+        let scale = GradeScale {
+            levels: vec![GradeLevel {
+                min_score: 60.0,
+                name: "P".to_string(),
+            }],
+            points: vec![GradePoint {
+                min_score: 60.0,
+                point: 1.0,
+            }],
+        };
+        assert_eq!(scale.level(59.0), "");
+        assert_eq!(scale.point(59.0), 0.0);
+    }
+
+    #[test]
+    fn distribution_counts_each_band() {
+        let distribution = Distribution::of([95.0, 85.0, 80.0, 70.0, 62.0, 59.0, 0.0, 75.0]);
+        assert_eq!(distribution.total(), 8);
+        assert_eq!(distribution.count(GradeBand::Excellent), 2);
+        assert_eq!(distribution.count(GradeBand::Good), 2);
+        assert_eq!(distribution.count(GradeBand::Fail), 2);
+        assert_eq!(distribution.rate(GradeBand::Excellent), 0.25);
+        assert_eq!(distribution.pass_rate(), 0.75);
+        assert_eq!(Distribution::default().rate(GradeBand::Pass), 0.0);
+    }
+}
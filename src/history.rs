@@ -0,0 +1,129 @@
+//! 输入区、三张数据表与已应用调分曲线的撤销/重做记录
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+
+use crate::app_state::{AppState, FinalItem, GroupItem, ProjectItem};
+use crate::curve::Curve;
+
+/// 最多保留的撤销步数
+const MAX_ENTRIES: usize = 100;
+
+/// 可撤销的数据：输入区、三张表与已应用的调分曲线
+#[derive(Clone, PartialEq)]
+pub struct Snapshot {
+    pub(crate) input_text: String,
+    final_table: Vec<FinalItem>,
+    project_table: Vec<ProjectItem>,
+    group_table: Vec<GroupItem>,
+    applied_curve: Option<Curve>,
+}
+
+impl Snapshot {
+    pub fn take(state: &AppState) -> Self {
+        Self {
+            input_text: state.input_text.clone(),
+            final_table: state.final_table.clone(),
+            project_table: state.project_table.clone(),
+            group_table: state.group_table.clone(),
+            applied_curve: state.curve.applied,
+        }
+    }
+
+    /// 以当前的数据表与编辑前的输入区构造快照，用于记录输入区的编辑
+    pub fn with_input(state: &AppState, input_text: String) -> Self {
+        Self {
+            input_text,
+            final_table: state.final_table.clone(),
+            project_table: state.project_table.clone(),
+            group_table: state.group_table.clone(),
+            applied_curve: state.curve.applied,
+        }
+    }
+
+    /// 将状态恢复为快照中的数据
+    pub fn restore(self, state: &mut AppState) {
+        self.swap(state);
+    }
+
+    /// 用快照替换状态中的数据，返回替换前的数据
+    fn swap(self, state: &mut AppState) -> Self {
+        if self.input_text != state.input_text {
+            state.input_errors.clear();
+        }
+        state.revision += 1;
+        state.dirty = true;
+        Self {
+            input_text: std::mem::replace(&mut state.input_text, self.input_text),
+            final_table: std::mem::replace(&mut state.final_table, self.final_table),
+            project_table: std::mem::replace(&mut state.project_table, self.project_table),
+            group_table: std::mem::replace(&mut state.group_table, self.group_table),
+            applied_curve: std::mem::replace(&mut state.curve.applied, self.applied_curve),
+        }
+    }
+}
+
+pub struct HistoryEntry {
+    pub label: String,
+    pub time: DateTime<Local>,
+    /// 该操作执行前(撤销栈)或撤销前(重做栈)的数据
+    snapshot: Snapshot,
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: VecDeque<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// 记录一次操作，`before` 为操作前的数据。新的操作会清空重做栈
+    pub fn push(&mut self, label: &str, before: Snapshot) {
+        self.undo.push_back(HistoryEntry {
+            label: label.to_string(),
+            time: Local::now(),
+            snapshot: before,
+        });
+        if self.undo.len() > MAX_ENTRIES {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+    }
+
+    /// 已执行、可撤销的操作，最早的在前
+    pub fn undo_entries(&self) -> &VecDeque<HistoryEntry> {
+        &self.undo
+    }
+
+    /// 已撤销、可重做的操作，最近撤销的在后
+    pub fn redo_entries(&self) -> &[HistoryEntry] {
+        &self.redo
+    }
+}
+
+impl AppState {
+    /// 撤销最近一次操作，返回该操作的名称
+    pub fn undo(&mut self) -> Option<String> {
+        let entry = self.history.undo.pop_back()?;
+        let current = entry.snapshot.swap(self);
+        let label = entry.label.clone();
+        self.history.redo.push(HistoryEntry {
+            snapshot: current,
+            ..entry
+        });
+        Some(label)
+    }
+
+    /// 重做最近一次撤销的操作，返回该操作的名称
+    pub fn redo(&mut self) -> Option<String> {
+        let entry = self.history.redo.pop()?;
+        let current = entry.snapshot.swap(self);
+        let label = entry.label.clone();
+        self.history.undo.push_back(HistoryEntry {
+            snapshot: current,
+            ..entry
+        });
+        Some(label)
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use calamine::{open_workbook_auto, Data, Reader};
+use chrono::{DateTime, Utc};
+use encoding_rs::GBK;
+
+use crate::app_state::*;
+use crate::deadline::parse_time;
+use crate::score::Score;
+
+/// 学号列可能使用的表头
+const STUDENT_ID_HEADERS: [&str; 5] = ["学号", "学生学号", "学工号", "student_id", "studentid"];
+/// PTA 导出中不属于分项成绩的列
+const META_HEADERS: [&str; 12] = [
+    "姓名", "账号", "用户名", "昵称", "邮箱", "班级", "序号", "排名", "总分", "提交时间", "name", "email",
+];
+const TOTAL_HEADER: &str = "总分";
+/// 提交时间列的表头；每列一个分项时，分项的提交时间列为分项表头加上该后缀，如 "作业1提交时间"
+const SUBMITTED_HEADER: &str = "提交时间";
+
+/// 组号列可能使用的表头
+const GROUP_ID_HEADERS: [&str; 4] = ["组号", "组别", "group_id", "group"];
+/// Project 成绩表中不属于分项成绩的列
+const PROJECT_META_HEADERS: [&str; 5] = ["组名", "组长", "总分", "展示分数", "展示报告 ID"];
+const PRESENTATION_SCORE_HEADER: &str = "展示分数";
+const PRESENTATION_ID_HEADER: &str = "展示报告 ID";
+
+/// 导入结果，用于写入输出区域
+#[derive(Default)]
+pub struct ImportReport {
+    /// 匹配所用的键，如 "学号" 或 "组号"
+    pub key_label: &'static str,
+    pub matched: usize,
+    /// 文件中存在但成绩表中没有的键
+    pub unknown_ids: Vec<String>,
+    /// 成绩表中存在但文件中没有的键
+    pub missing_ids: Vec<String>,
+    /// 文件中重复出现的键，只使用第一次出现的行
+    pub duplicated_ids: Vec<String>,
+    /// 提交时间无法识别的键，这些提交时间记为没有记录
+    pub invalid_times: Vec<String>,
+    /// 展示分数为空或无法识别的键，这些组保留原来的展示分数
+    pub invalid_scores: Vec<String>,
+}
+
+impl ImportReport {
+    fn new(key_label: &'static str) -> Self {
+        Self {
+            key_label,
+            ..Default::default()
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!("成功匹配 {} 个{}", self.matched, self.key_label)];
+        if !self.unknown_ids.is_empty() {
+            lines.push(format!("成绩表中不存在的{}: {}", self.key_label, self.unknown_ids.join(",")));
+        }
+        if !self.missing_ids.is_empty() {
+            lines.push(format!("文件中缺少的{}: {}", self.key_label, self.missing_ids.join(",")));
+        }
+        if !self.duplicated_ids.is_empty() {
+            lines.push(format!("文件中重复的{}: {}", self.key_label, self.duplicated_ids.join(",")));
+        }
+        if !self.invalid_times.is_empty() {
+            lines.push(format!("提交时间无法识别的{}: {}", self.key_label, self.invalid_times.join(",")));
+        }
+        if !self.invalid_scores.is_empty() {
+            lines.push(format!(
+                "展示分数为空或无法识别的{}(保留原分数): {}",
+                self.key_label,
+                self.invalid_scores.join(",")
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// CSV 文件的内容：先按 UTF-8(可带 BOM)解码，失败时按 GBK 解码(Excel 在中文系统中默认导出 GBK)
+fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => GBK.decode_without_bom_handling(bytes).0.into_owned(),
+    }
+}
+
+/// Excel 单元格的文本。设置了日期格式的单元格内部是日期序列号，转换为不带时区的时间文本，
+/// 由 `parse_time` 按课程时区解析；普通数字保持原样，不会被当作日期
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(time) if time.is_datetime() => time
+            .as_datetime()
+            .map_or_else(|| cell.to_string(), |time| time.format("%Y-%m-%d %H:%M:%S").to_string()),
+        _ => cell.to_string(),
+    }
+}
+
+/// 读取 CSV 或 Excel 文件的第一个工作表，返回所有单元格的文本
+pub fn read_sheet(path: &str) -> Result<Vec<Vec<String>>, String> {
+    let extension = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "csv" | "txt" => {
+            let text = decode_text(&std::fs::read(path).map_err(|e| e.to_string())?);
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_reader(text.as_bytes());
+            let mut rows = Vec::new();
+            for record in reader.records() {
+                let record = record.map_err(|e| e.to_string())?;
+                rows.push(record.iter().map(|cell| cell.trim().to_string()).collect());
+            }
+            Ok(rows)
+        }
+        "xlsx" | "xlsm" | "xls" | "ods" => {
+            let mut workbook = open_workbook_auto(path).map_err(|e| e.to_string())?;
+            let range = workbook
+                .worksheet_range_at(0)
+                .ok_or("文件中没有工作表".to_string())?
+                .map_err(|e| e.to_string())?;
+            Ok(range
+                .rows()
+                .map(|row| row.iter().map(|cell| cell_text(cell).trim().to_string()).collect())
+                .collect())
+        }
+        _ => Err(format!("不支持的文件类型: {}", path)),
+    }
+}
+
+pub(crate) fn normalize_header(header: &str) -> String {
+    header.trim().to_lowercase().replace([' ', '_'], "")
+}
+
+fn header_position(headers: &[String], header: &str) -> Option<usize> {
+    let header = normalize_header(header);
+    headers.iter().position(|h| normalize_header(h) == header)
+}
+
+/// 表头中除 `key_col` 与 `meta_headers` 以外的列
+fn score_columns(headers: &[String], key_col: usize, meta_headers: &[&str]) -> Vec<usize> {
+    let meta: Vec<String> = meta_headers.iter().map(|h| normalize_header(h)).collect();
+    (0..headers.len())
+        .filter(|&col| col != key_col && !meta.contains(&normalize_header(&headers[col])))
+        .collect()
+}
+
+/// 返回表头所在行号以及该行中 `candidates` 对应的列号
+pub fn find_header(sheet: &[Vec<String>], candidates: &[&str]) -> Option<(usize, usize)> {
+    let candidates: Vec<String> = candidates.iter().map(|c| normalize_header(c)).collect();
+    sheet.iter().enumerate().find_map(|(row_index, row)| {
+        row.iter()
+            .position(|cell| candidates.contains(&normalize_header(cell)))
+            .map(|col| (row_index, col))
+    })
+}
+
+/// 单个分数，空白、"-" 或无法识别时为 None
+fn parse_score(cell: &str) -> Option<f32> {
+    cell.trim().parse::<f32>().ok().filter(|value| value.is_finite() && *value >= 0.0)
+}
+
+/// 分项成绩：空白或无法识别的单元格(如 PTA 中未提交的 "-")记为未提交
+fn read_scores(row: &[String], cols: &[usize]) -> Vec<Score> {
+    cols.iter()
+        .map(|&col| row.get(col).and_then(|cell| cell.parse().ok()).unwrap_or(Score::Missing))
+        .collect()
+}
+
+/// 每个分项的提交时间，没有记录时为 None
+type SubmittedTimes = Vec<Option<DateTime<Utc>>>;
+
+/// 提交时间：空白的单元格记为没有记录，无法识别时返回 Err
+fn read_times(row: &[String], cols: &[Option<usize>]) -> Result<SubmittedTimes, ()> {
+    cols.iter()
+        .map(|col| match col.and_then(|col| row.get(col)).map(|cell| cell.trim()) {
+            None | Some("") | Some("-") => Ok(None),
+            Some(cell) => parse_time(cell).map(Some).ok_or(()),
+        })
+        .collect()
+}
+
+/// `item_index` 为 0 时替换全部分项，否则写入第 `item_index` 个分项，不足时以 `fill` 补齐
+fn write_items<T: Clone>(target: &mut Vec<T>, values: &[T], item_index: usize, fill: T) {
+    if item_index == 0 {
+        *target = values.to_vec();
+    } else {
+        if target.len() < item_index {
+            target.resize(item_index, fill);
+        }
+        target[item_index - 1] = values[0].clone();
+    }
+}
+
+/// 从 PTA 导出的成绩文件中读取分项成绩，按学号写入 `final_table`。
+///
+/// `item_index` 为 0 时，除学号、姓名等信息列外的每一列都视为一个分项；
+/// 否则只读取 "总分" 列，写入第 `item_index` 个分项（适用于每个题目集单独导出的情况）。
+/// 导入作业时同时读取提交时间：前者为各分项的 "<分项>提交时间" 列，后者为 "提交时间" 列。
+pub fn import_item_scores(
+    table: &mut [FinalItem],
+    sheet: &[Vec<String>],
+    target: ScoreTarget,
+    item_index: usize,
+) -> Result<ImportReport, String> {
+    let (header_row, id_col) = find_header(sheet, &STUDENT_ID_HEADERS).ok_or("找不到学号列".to_string())?;
+    let headers = &sheet[header_row];
+    let (score_cols, time_cols): (Vec<usize>, Vec<Option<usize>>) = if item_index == 0 {
+        let score_cols: Vec<usize> = score_columns(headers, id_col, &META_HEADERS)
+            .into_iter()
+            .filter(|&col| !normalize_header(&headers[col]).ends_with(SUBMITTED_HEADER))
+            .collect();
+        // 否则会把所有学生的分项清空
+        if score_cols.is_empty() {
+            return Err("没有分项列，请指定分项序号".to_string());
+        }
+        let time_cols = score_cols
+            .iter()
+            .map(|&col| header_position(headers, &format!("{}{}", headers[col], SUBMITTED_HEADER)))
+            .collect();
+        (score_cols, time_cols)
+    } else {
+        let total_col = header_position(headers, TOTAL_HEADER).ok_or("找不到总分列".to_string())?;
+        (vec![total_col], vec![header_position(headers, SUBMITTED_HEADER)])
+    };
+
+    let mut report = ImportReport::new("学号");
+    let mut rows: HashMap<&str, (Vec<Score>, SubmittedTimes)> = HashMap::new();
+    let known_ids: HashSet<&str> = table.iter().map(|item| item.student_info.student_id.as_str()).collect();
+    for row in &sheet[header_row + 1..] {
+        let Some(id) = row.get(id_col).map(|id| id.trim()).filter(|id| !id.is_empty()) else {
+            continue;
+        };
+        if !known_ids.contains(id) {
+            report.unknown_ids.push(id.to_string());
+            continue;
+        }
+        if rows.contains_key(id) {
+            report.duplicated_ids.push(id.to_string());
+            continue;
+        }
+        let times = read_times(row, &time_cols).unwrap_or_else(|_| {
+            report.invalid_times.push(id.to_string());
+            vec![None; time_cols.len()]
+        });
+        rows.insert(id, (read_scores(row, &score_cols), times));
+    }
+
+    for item in table.iter_mut() {
+        let Some((scores, times)) = rows.get(item.student_info.student_id.as_str()) else {
+            report.missing_ids.push(item.student_info.student_id.clone());
+            continue;
+        };
+        let target_scores = match target {
+            ScoreTarget::Homework => &mut item.homework_score,
+            ScoreTarget::Discussion => &mut item.discussion_score,
+        };
+        write_items(target_scores, scores, item_index, Score::Missing);
+        if target == ScoreTarget::Homework {
+            write_items(&mut item.homework_submitted, times, item_index, None);
+        }
+        report.matched += 1;
+    }
+    Ok(report)
+}
+
+/// 从助教给出的报告评分表或互评(PR)表中读取成绩，按组号写入 `project_table`。
+///
+/// 除组号等信息列外的每一列都视为一个分项；报告评分表中若有 "展示分数"、"展示报告 ID" 列，
+/// 也会一并写入。
+pub fn import_project_scores(
+    table: &mut [ProjectItem],
+    sheet: &[Vec<String>],
+    target: ProjectScoreTarget,
+) -> Result<ImportReport, String> {
+    let (header_row, id_col) = find_header(sheet, &GROUP_ID_HEADERS).ok_or("找不到组号列".to_string())?;
+    let headers = &sheet[header_row];
+    let score_cols = score_columns(headers, id_col, &PROJECT_META_HEADERS);
+    let presentation_score_col = header_position(headers, PRESENTATION_SCORE_HEADER);
+    let presentation_id_col = header_position(headers, PRESENTATION_ID_HEADER);
+
+    let mut report = ImportReport::new("组号");
+    let mut rows: HashMap<usize, &Vec<String>> = HashMap::new();
+    let known_ids: HashSet<usize> = table.iter().map(|item| item.group_id).collect();
+    for row in &sheet[header_row + 1..] {
+        let Some(raw_id) = row.get(id_col).map(|id| id.trim()).filter(|id| !id.is_empty()) else {
+            continue;
+        };
+        let Some(id) = raw_id.parse::<usize>().ok().filter(|id| known_ids.contains(id)) else {
+            report.unknown_ids.push(raw_id.to_string());
+            continue;
+        };
+        if rows.contains_key(&id) {
+            report.duplicated_ids.push(raw_id.to_string());
+            continue;
+        }
+        rows.insert(id, row);
+    }
+
+    for item in table.iter_mut() {
+        let Some(row) = rows.get(&item.group_id) else {
+            report.missing_ids.push(item.group_id.to_string());
+            continue;
+        };
+        match target {
+            ProjectScoreTarget::Report => {
+                item.report_score = read_scores(row, &score_cols);
+                if let Some(col) = presentation_score_col {
+                    match row.get(col).and_then(|cell| parse_score(cell)) {
+                        Some(score) => item.presentation_score = score,
+                        None => report.invalid_scores.push(item.group_id.to_string()),
+                    }
+                }
+                if let Some(id) = presentation_id_col
+                    .and_then(|col| row.get(col))
+                    .and_then(|cell| cell.trim().parse::<usize>().ok())
+                {
+                    item.presentation_id = id;
+                }
+            }
+            ProjectScoreTarget::Pr => item.pr_score = read_scores(row, &score_cols),
+        }
+        report.matched += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_score_rejects_blank_and_malformed_cells() {
+        assert_eq!(parse_score(" 5.5 "), Some(5.5));
+        for cell in ["", "-", "abc", "nan", "-1"] {
+            assert_eq!(parse_score(cell), None, "{:?}", cell);
+        }
+    }
+
+    #[test]
+    fn decode_text_accepts_utf8_with_bom_and_gbk() {
+        assert_eq!(decode_text("\u{feff}学号,姓名".as_bytes()), "学号,姓名");
+        assert_eq!(decode_text("学号,姓名".as_bytes()), "学号,姓名");
+        let (gbk, _, _) = GBK.encode("学号,姓名");
+        assert_eq!(decode_text(&gbk), "学号,姓名");
+    }
+
+    #[test]
+    fn only_date_formatted_cells_become_times() {
+        use calamine::{ExcelDateTime, ExcelDateTimeType};
+
+        let date = Data::DateTime(ExcelDateTime::new(45372.5, ExcelDateTimeType::DateTime, false));
+        assert_eq!(cell_text(&date), "2024-03-21 12:00:00");
+        assert_eq!(cell_text(&Data::Float(45372.5)), "45372.5");
+        assert!(parse_time(&cell_text(&Data::Float(45372.5))).is_none());
+    }
+
+    #[test]
+    fn sheets_without_item_columns_need_an_item_index() {
+        let sheet: Vec<Vec<String>> = [["学号", "姓名", "总分"], ["1", "张三", "9"]]
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect();
+        let mut table = vec![FinalItem {
+            student_info: StudentItem {
+                student_id: "1".to_string(),
+                student_name: "张三".to_string(),
+            },
+            homework_score: vec![Score::Scored(8.0)],
+            ..Default::default()
+        }];
+        assert!(import_item_scores(&mut table, &sheet, ScoreTarget::Homework, 0).is_err());
+        assert_eq!(table[0].homework_score, vec![Score::Scored(8.0)]);
+
+        let report = import_item_scores(&mut table, &sheet, ScoreTarget::Homework, 2).unwrap();
+        assert_eq!(report.matched, 1);
+        assert_eq!(table[0].homework_score, vec![Score::Scored(8.0), Score::Scored(9.0)]);
+    }
+}
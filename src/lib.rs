@@ -0,0 +1,24 @@
+//! ADS 成绩统计的数据模型、解析、计算与持久化。
+//!
+//! 本库不依赖 eframe/egui，图形界面与命令行都建立在它之上，也可以直接用于编写脚本。
+
+pub mod app_state;
+pub mod assignment;
+pub mod columns;
+pub mod curve;
+pub mod deadline;
+pub mod export;
+pub mod grade;
+pub mod history;
+pub mod importer;
+pub mod parse_error;
+pub mod paste;
+pub mod pinyin;
+pub mod random;
+pub mod schema;
+pub mod score;
+pub mod stats;
+pub mod sync;
+pub mod table_io;
+pub mod table_view;
+pub mod utils;
@@ -1,22 +1,60 @@
+mod cli;
+#[cfg(feature = "gui")]
 mod pages {
+    pub mod autosave;
     pub mod startup;
 }
+#[cfg(feature = "gui")]
 mod plugins {
-    pub mod app_state;
     pub mod build_table_data;
+    pub mod curve;
+    pub mod export;
+    pub mod history;
+    pub mod importer;
+    pub mod paste;
     pub mod random_group;
     pub mod random_selection;
+    pub mod settings;
+    pub mod statistics;
+    pub mod student_detail;
     pub mod table;
+    pub mod table_io;
 }
-pub mod utils;
 
-use pages::startup::MyEguiApp;
+use std::process::ExitCode;
+
+use clap::Parser;
+use cli::Cli;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Some(command) => cli::run(command),
+        None => run_gui(),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+fn run_gui() -> Result<(), String> {
+    use pages::startup::MyEguiApp;
 
-fn main() -> Result<(), eframe::Error> {
     let native_options = eframe::NativeOptions::default();
     eframe::run_native(
         "ADS 助教终端",
         native_options,
         Box::new(|cc| Ok(Box::new(MyEguiApp::new(cc)))),
     )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "gui"))]
+fn run_gui() -> Result<(), String> {
+    Err("未启用图形界面(gui feature)，请使用子命令，见 ads --help".to_string())
 }
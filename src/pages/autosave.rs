@@ -0,0 +1,59 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ads::app_state::{AppSingleton, AppState};
+use eframe::egui;
+
+/// 最后一次修改后等待多久再写盘
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// 只在数据被标记为已修改(`AppState::dirty`)后保存，并将连续的修改合并为一次写盘
+#[derive(Default)]
+pub struct AutoSaver {
+    /// 最近一次检测到修改的时间，None 表示没有待保存的修改
+    changed_at: Option<Instant>,
+}
+
+impl AutoSaver {
+    /// 每帧调用。读取并清除修改标记，最后一次修改 `SAVE_DELAY` 后写盘
+    pub fn update(&mut self, ctx: &egui::Context, state: &Arc<Mutex<AppState>>) {
+        {
+            let mut state = state.lock().unwrap();
+            if state.dirty {
+                state.dirty = false;
+                self.changed_at = Some(Instant::now());
+            }
+        }
+
+        if let Some(changed_at) = self.changed_at {
+            let elapsed = changed_at.elapsed();
+            if elapsed >= SAVE_DELAY {
+                self.save(state);
+            } else {
+                ctx.request_repaint_after(SAVE_DELAY - elapsed);
+            }
+        }
+    }
+
+    /// 立即保存尚未写盘的修改，用于关闭窗口时
+    pub fn flush(&mut self, state: &Arc<Mutex<AppState>>) {
+        if self.changed_at.is_some() || state.lock().unwrap().dirty {
+            self.save(state);
+        }
+    }
+
+    fn save(&mut self, state: &Arc<Mutex<AppState>>) {
+        let mut state = state.lock().unwrap();
+        match AppSingleton::save_state(&state) {
+            Ok(()) => {
+                state.dirty = false;
+                self.changed_at = None;
+            }
+            Err(e) => {
+                eprintln!("Failed to save state: {}", e);
+                // 稍后重试
+                self.changed_at = Some(Instant::now());
+            }
+        }
+    }
+}
@@ -1,22 +1,86 @@
-use crate::{
-    plugins::{
-        app_state::{AppSingleton, FinalItem, GroupItem, Log, ProjectItem, TableType},
-        build_table_data::{
-            build_final_from_input, 
-            build_group_from_input, 
-            build_project_from_group,
-            find_student_no_group,
-        },
-        random_group::random_group,
-        random_selection::random_selection,
-        table::table_ui,
+use crate::pages::autosave::AutoSaver;
+use crate::plugins::{
+    build_table_data::{
+        build_final_from_input, 
+        build_group_from_input, 
+        build_project_from_group,
+        find_student_no_group,
+        sync_project_to_final,
     },
-    utils::{load_fonts, split_to_table},
+    curve::curve_ui,
+    export::export_excel,
+    history::{history_shortcuts, history_ui},
+    importer::{import_item_scores_ui, import_project_scores_ui},
+    paste::{paste_ui, paste_window},
+    random_group::random_group,
+    random_selection::random_selection,
+    settings::{grade_scale_ui, grading_policy_ui, project_rules_ui},
+    statistics::{statistics_window, StatisticsPanel},
+    student_detail::{student_detail_window, StudentDetail},
+    table::{table_ui, TableCache},
+    table_io::{import_preview_window, table_io_ui},
 };
+use std::collections::HashSet;
+
+use ads::app_state::{AppSingleton, TableType};
+use ads::history::Snapshot;
 use eframe::egui;
 
-#[derive(Default)]
-pub struct MyEguiApp {}
+pub fn load_fonts(ctx: &egui::Context) {
+    let mut fonts = egui::FontDefinitions::default();
+    fonts.font_data.insert("my_font".to_owned(),
+    egui::FontData::from_static(include_bytes!("../fonts/hei.ttf")));
+    fonts.families.get_mut(&egui::FontFamily::Proportional).unwrap()
+        .insert(0, "my_font".to_owned());
+    fonts.families.get_mut(&egui::FontFamily::Monospace).unwrap()
+        .push("my_font".to_owned());
+    ctx.set_fonts(fonts);
+}
+
+/// 输入区排版：`error_lines` 中的行(从 1 开始)以红色背景标出
+fn highlight_lines(ui: &egui::Ui, text: &str, error_lines: &HashSet<usize>) -> egui::text::LayoutJob {
+    let font_id = egui::FontSelection::default().resolve(ui.style());
+    let normal = egui::TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+    let error = egui::TextFormat {
+        background: ui.visuals().error_fg_color.gamma_multiply(0.3),
+        ..normal.clone()
+    };
+    let mut job = egui::text::LayoutJob::default();
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let format = if error_lines.contains(&(index + 1)) { &error } else { &normal };
+        job.append(line, 0.0, format.clone());
+    }
+    job
+}
+
+/// 启动时读取数据失败则弹窗提示，直到用户确认
+fn load_error_window(ctx: &egui::Context) {
+    let state = AppSingleton::instance();
+    let mut state = state.lock().unwrap();
+    let Some(error) = state.load_error.clone() else {
+        return;
+    };
+    egui::Window::new("读取数据失败")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label(error);
+            ui.label("当前以空数据启动，修改后将写入新的 app_state.json。");
+            if ui.button("我知道了").clicked() {
+                state.load_error = None;
+            }
+        });
+}
+
+pub struct MyEguiApp {
+    saver: AutoSaver,
+    /// 输入区开始编辑前的文本，失去焦点时写入历史记录
+    input_before_edit: Option<String>,
+    table_cache: TableCache,
+    statistics: StatisticsPanel,
+    student_detail: StudentDetail,
+}
 
 impl MyEguiApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
@@ -25,12 +89,25 @@ impl MyEguiApp {
         // Use the cc.gl (a glow::Context) to create graphics shaders and buffers that you can use
         // for e.g. egui::PaintCallback.
         load_fonts(&cc.egui_ctx);
-        Self::default()
+        Self {
+            saver: AutoSaver::default(),
+            input_before_edit: None,
+            table_cache: TableCache::default(),
+            statistics: StatisticsPanel::default(),
+            student_detail: StudentDetail::default(),
+        }
     }
 }
 
 impl eframe::App for MyEguiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        load_error_window(ctx);
+        history_shortcuts(AppSingleton::instance(), ctx);
+        paste_window(AppSingleton::instance(), ctx);
+        import_preview_window(AppSingleton::instance(), ctx);
+        statistics_window(AppSingleton::instance(), &mut self.statistics, ctx);
+        student_detail_window(AppSingleton::instance(), &mut self.student_detail, ctx);
+
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
                 let state = AppSingleton::instance();
@@ -40,17 +117,52 @@ impl eframe::App for MyEguiApp {
                         ui.heading("IO 区域");
                         ui.label("输入区域");
                         let mut state = state.lock().unwrap();
-                        egui::TextEdit::multiline(&mut state.input_text)
+                        let error_lines: HashSet<usize> = state.input_errors.iter().map(|e| e.line).collect();
+                        let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                            let mut job = highlight_lines(ui, text, &error_lines);
+                            job.wrap.max_width = wrap_width;
+                            ui.fonts(|f| f.layout_job(job))
+                        };
+                        let mut input = egui::TextEdit::multiline(&mut state.input_text)
                             .hint_text("输入的数据以英文逗号或换行进行分割")
                             .desired_width(500.0)
-                            .desired_rows(10)
-                            .show(ui);
+                            .desired_rows(10);
+                        // 没有出错的行时使用默认排版，避免每帧为大段文本重建排版任务
+                        if !error_lines.is_empty() {
+                            input = input.layouter(&mut layouter);
+                        }
+                        let input = input.show(ui);
+                        if input.response.changed() {
+                            state.input_errors.clear();
+                            state.mark_dirty();
+                        }
+                        // 一次编辑(从获得焦点到失去焦点)记为一条可撤销记录
+                        if input.response.gained_focus() {
+                            self.input_before_edit = Some(state.input_text.clone());
+                        }
+                        if input.response.lost_focus() {
+                            if let Some(before) = self.input_before_edit.take() {
+                                if before != state.input_text {
+                                    let snapshot = Snapshot::with_input(&state, before);
+                                    state.history.push("编辑输入区", snapshot);
+                                }
+                            }
+                        }
+                        if !state.input_errors.is_empty() {
+                            ui.colored_label(
+                                ui.visuals().error_fg_color,
+                                format!("{} 行解析失败，已用红色标出", state.input_errors.len()),
+                            );
+                            for error in state.input_errors.iter().take(5) {
+                                ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+                            }
+                        }
                         ui.label("输出区域");
                         egui::ScrollArea::vertical()
                             .min_scrolled_height(100.0)
                             .max_width(500.0)
                             .show(ui, |ui| {
-                                for log in (&state).output_text.iter().rev() {
+                                for log in state.output_text.iter().rev() {
                                     ui.label(log.to_string());
                                 }
                             });
@@ -81,6 +193,31 @@ impl eframe::App for MyEguiApp {
 
                         // 筛选未组队同学
                         find_student_no_group(state.clone(), ui);
+
+                        // 按组队名单同步 Project 成绩到总评
+                        sync_project_to_final(state.clone(), ui);
+
+                        // 从 PTA 导出文件导入作业/讨论分项
+                        import_item_scores_ui(state.clone(), ui);
+
+                        // 导入 Project 报告/PR 评分表
+                        import_project_scores_ui(state.clone(), ui);
+
+                        // 导出三张表到 Excel
+                        export_excel(state.clone(), ui);
+
+                        ui.separator();
+
+                        // 计算规则设置
+                        grading_policy_ui(state.clone(), ui);
+                        project_rules_ui(state.clone(), ui);
+                        grade_scale_ui(state.clone(), ui);
+
+                        // 等级分布检查与调分
+                        curve_ui(state.clone(), ui);
+
+                        // 撤销/重做
+                        history_ui(state.clone(), ui);
                     });
                 });
 
@@ -93,114 +230,64 @@ impl eframe::App for MyEguiApp {
 
                         if ui.button("总评成绩").clicked() {
                             state.table_type = TableType::FinalScore;
+                            state.mark_dirty();
                         }
                         if ui.button("Project 成绩").clicked() {
                             state.table_type = TableType::ProjectScore;
+                            state.mark_dirty();
                         }
                         if ui.button("组队名单").clicked() {
                             state.table_type = TableType::GroupList;
+                            state.mark_dirty();
                         }
-                        if ui.button("Swap").clicked() {
-                            let input_text = state.input_text.clone();
-                            let intput_vec = split_to_table(input_text);
-                            match state.table_type {
-                                TableType::FinalScore => {
-                                    let new_table: Vec<FinalItem> = intput_vec
-                                        .iter()
-                                        .map(|row| match FinalItem::from_vec(row) {
-                                            Ok(item) => item,
-                                            Err(e) => {
-                                                state.output_text.push(Log::new(
-                                                    "Swap Info".to_string(),
-                                                    e.to_string(),
-                                                ));
-                                                FinalItem::default()
-                                            }
-                                        })
-                                        .collect();
-                                    let table_vec: Vec<String> = state
-                                        .final_table
-                                        .iter()
-                                        .map(|row| row.to_vec().join(","))
-                                        .collect();
-                                    state.input_text = table_vec.join("\n");
-                                    state.final_table = new_table;
-                                }
-                                TableType::ProjectScore => {
-                                    let new_table: Vec<ProjectItem> = intput_vec
-                                        .iter()
-                                        .map(|row| match ProjectItem::from_vec(row) {
-                                            Ok(item) => item,
-                                            Err(e) => {
-                                                state.output_text.push(Log::new(
-                                                    "Swap Info".to_string(),
-                                                    e.to_string(),
-                                                ));
-                                                ProjectItem::default()
-                                            }
-                                        })
-                                        .collect();
-                                    let table_vec: Vec<String> = state
-                                        .project_table
-                                        .iter()
-                                        .map(|row| row.to_vec().join(", "))
-                                        .collect();
-                                    state.input_text = table_vec.join("\n");
-                                    state.project_table = new_table;
-                                }
-                                TableType::GroupList => {
-                                    let new_table: Vec<GroupItem> = intput_vec
-                                        .iter()
-                                        .map(|row| match GroupItem::from_vec(row) {
-                                            Ok(item) => item,
-                                            Err(e) => {
-                                                state.output_text.push(Log::new(
-                                                    "Swap Info".to_string(),
-                                                    e.to_string(),
-                                                ));
-                                                GroupItem::default()
-                                            }
-                                        })
-                                        .collect();
-                                    let table_vec: Vec<String> = state
-                                        .group_table
-                                        .iter()
-                                        .map(|row| row.to_vec().join(", "))
-                                        .collect();
-                                    state.input_text = table_vec.join("\n");
-                                    state.group_table = new_table;
-                                }
-                            }
-                        }
-                        if ui.button("统计分数").clicked() {
-                            match state.table_type {
-                                TableType::GroupList => {
-                                    state.output_text.push(Log::new(
-                                        "统计分数 - GroupList".to_string(),
-                                        "统计完毕".to_string(),
-                                    ));
-                                }
-                                TableType::ProjectScore => {
-                                    let project_table = &mut state.project_table;
-                                    for row in project_table.iter_mut() {
-                                        row.calaculate();
+                        table_io_ui(&mut state, ui);
+                        paste_ui(&mut state, ui);
+                        if ui.button("统计分数").on_hover_text("重新计算当前表并显示成绩分布").clicked() {
+                            self.statistics.open = true;
+                            state.edit("统计分数", |state| {
+                                match state.table_type {
+                                    TableType::GroupList => {
+                                        state.log(
+                                            "统计分数 - GroupList".to_string(),
+                                            "统计完毕".to_string(),
+                                        );
                                     }
-                                    state.output_text.push(Log::new(
-                                        "统计分数 - ProjectScore".to_string(),
-                                        "重新计算 Project总分, Bonus分数, ".to_string(),
-                                    ));
-                                }
-                                TableType::FinalScore => {
-                                    let project_table: &mut Vec<FinalItem> = &mut state.final_table;
-                                    for row in project_table.iter_mut() {
-                                        row.calaculate();
+                                    TableType::ProjectScore => {
+                                        let mismatched = state.calculate_project_table();
+                                        state.log(
+                                            "统计分数 - ProjectScore".to_string(),
+                                            "重新计算 Project总分, Bonus分数, ".to_string(),
+                                        );
+                                        if !mismatched.is_empty() {
+                                            let rules = &state.project_rules;
+                                            let message = format!(
+                                                "以下组的报告/PR 数量与规则({} 份报告, {} 个 PR)不一致: {}",
+                                                rules.report_count,
+                                                rules.pr_reviewer_count,
+                                                mismatched
+                                                    .iter()
+                                                    .map(|id| id.to_string())
+                                                    .collect::<Vec<String>>()
+                                                    .join(",")
+                                            );
+                                            state.log(
+                                                "统计分数 - ProjectScore".to_string(),
+                                                message,
+                                            );
+                                        }
+                                    }
+                                    TableType::FinalScore => {
+                                        state.calculate_final_table();
+                                        state.log(
+                                            "统计分数 - FinalScore".to_string(),
+                                            "重新计算 总分, 平时分, 作业分, 讨论分".to_string(),
+                                        );
                                     }
-                                    state.output_text.push(Log::new(
-                                        "统计分数 - FinalScore".to_string(),
-                                        "重新计算 总分, 平时分, 作业分, 讨论分".to_string(),
-                                    ));
                                 }
-                            }
+                            });
+                        }
+                        if ui.button("学生详情").on_hover_text("查看一名学生的各项成绩与迟交扣分").clicked() {
+                            self.student_detail.open = true;
                         }
                     });
                     use egui_extras::{Size, StripBuilder};
@@ -209,17 +296,18 @@ impl eframe::App for MyEguiApp {
                         .vertical(|mut strip| {
                             strip.cell(|ui| {
                                 egui::ScrollArea::horizontal().show(ui, |ui| {
-                                    table_ui(state.clone(), ui);
+                                    table_ui(state.clone(), &mut self.table_cache, ui);
                                 });
                             });
                         });
                 }
-
-                let state = state.lock().unwrap();
-                if let Err(e) = AppSingleton::save_state(&state) {
-                    eprintln!("Failed to save state: {}", e);
-                };
             });
         });
+
+        self.saver.update(ctx, &AppSingleton::instance());
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.saver.flush(&AppSingleton::instance());
     }
 }
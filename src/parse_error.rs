@@ -0,0 +1,102 @@
+//! 解析输入区数据时的错误，带有出错的行、列、字段名与原始文本
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    /// 字段数量不足
+    Incomplete,
+    /// 无法解析为数字
+    InvalidNumber,
+    /// 缺少分项成绩的结束标记，如 `discussionEnd`
+    MissingMarker(&'static str),
+    /// 学号后缺少姓名
+    MissingName,
+    /// 与前面的行重复的学号/组号
+    Duplicate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// 行号，从 1 开始；0 表示未知
+    pub line: usize,
+    /// 列号，从 1 开始；0 表示整行
+    pub column: usize,
+    /// 字段名，如 "期末"
+    pub field: String,
+    /// 出错单元格的原始文本
+    pub raw: String,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, column: usize, field: &str, raw: &str) -> Self {
+        Self {
+            line: 0,
+            column,
+            field: field.to_string(),
+            raw: raw.to_string(),
+            kind,
+        }
+    }
+
+    /// 设置出错的行号
+    pub fn at_line(self, line: usize) -> Self {
+        Self { line, ..self }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "第 {} 行", self.line)?;
+        }
+        if self.column > 0 {
+            write!(f, "第 {} 列", self.column)?;
+        }
+        if !self.field.is_empty() {
+            write!(f, "({})", self.field)?;
+        }
+        if self.line > 0 || self.column > 0 || !self.field.is_empty() {
+            write!(f, ": ")?;
+        }
+        match &self.kind {
+            ParseErrorKind::Incomplete => write!(f, "数据不完整"),
+            ParseErrorKind::InvalidNumber => write!(f, "无法解析为数字 \"{}\"", self.raw),
+            ParseErrorKind::MissingMarker(marker) => write!(f, "缺少结束标记 '{}'", marker),
+            ParseErrorKind::MissingName => write!(f, "学号 \"{}\" 后缺少姓名", self.raw),
+            ParseErrorKind::Duplicate => write!(f, "重复的 \"{}\"", self.raw),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// 解析 `row` 中下标为 `index` 的单元格，失败时返回带列号与字段名的错误
+pub fn parse_cell<T: FromStr>(row: &[String], index: usize, field: &str) -> Result<T, ParseError> {
+    let raw = row
+        .get(index)
+        .ok_or_else(|| ParseError::new(ParseErrorKind::Incomplete, index + 1, field, ""))?;
+    raw.parse::<T>()
+        .map_err(|_| ParseError::new(ParseErrorKind::InvalidNumber, index + 1, field, raw))
+}
+
+/// 从 `start` 开始读取分项成绩直到 `marker`，返回成绩与标记所在的下标
+pub fn parse_until_marker<T: FromStr>(
+    row: &[String],
+    start: usize,
+    marker: &'static str,
+    field: &str,
+) -> Result<(Vec<T>, usize), ParseError> {
+    let mut scores = Vec::new();
+    let mut i = start;
+    while i < row.len() && row[i] != marker {
+        scores.push(parse_cell(row, i, field)?);
+        i += 1;
+    }
+    if i >= row.len() {
+        return Err(ParseError::new(ParseErrorKind::MissingMarker(marker), i + 1, field, ""));
+    }
+    Ok((scores, i))
+}
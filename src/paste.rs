@@ -0,0 +1,398 @@
+//! 识别粘贴到输入区的表格。
+//!
+//! 从 Excel、WPS 或网页复制的数据可能以制表符、逗号、分号或空格分隔，可能带表头、引号和全角标点。
+//! `PastedTable::parse` 统一处理这些情况，再由用户确认每一列对应的字段，
+//! 最后由 `PastedTable::to_text` 生成带表头的输入区格式，交给 `table_io::preview_import` 导入。
+
+use std::collections::HashMap;
+
+use crate::app_state::{FinalItem, GroupItem, ProjectItem, TableType};
+use crate::importer::normalize_header;
+use crate::table_io::TableRow;
+
+/// 按优先级排列的候选分隔符，都不存在时按连续空白分隔
+const DELIMITERS: [char; 4] = ['\t', ',', ';', '|'];
+/// 识别分隔符与表头时检查的行数
+const SAMPLE_LINES: usize = 20;
+
+/// 全角字符与特殊空白转换为半角，统一换行符
+pub fn normalize_text(text: &str) -> String {
+    text.trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n")
+        .chars()
+        .filter(|c| *c != '\r')
+        .map(|c| match c {
+            // 全角 ！ 到 ～ 与 ASCII 一一对应
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+            '\u{3000}' | '\u{a0}' => ' ',
+            '“' | '”' => '"',
+            '‘' | '’' => '\'',
+            _ => c,
+        })
+        .collect()
+}
+
+/// 按 `detect_delimiter` 的结果分割一行，空格分隔时连续空白视为一个分隔符
+pub fn split_cells(line: &str, delimiter: char) -> Vec<String> {
+    match delimiter {
+        ' ' => line.split_whitespace().map(|cell| cell.trim_matches('"').to_string()).collect(),
+        _ => split_line(line, delimiter),
+    }
+}
+
+/// 在引号之外按 `delimiter` 分割一行，`""` 表示引号本身；单元格去除首尾空白并合并连续空白
+pub fn split_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells.iter().map(|cell| collapse_whitespace(cell)).collect()
+}
+
+fn collapse_whitespace(cell: &str) -> String {
+    cell.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn count_outside_quotes(line: &str, delimiter: char) -> usize {
+    let mut quoted = false;
+    line.chars()
+        .filter(|&c| {
+            if c == '"' {
+                quoted = !quoted;
+            }
+            c == delimiter && !quoted
+        })
+        .count()
+}
+
+/// 选择在最多行中出现的候选分隔符，并列时按 `DELIMITERS` 的顺序；都不存在时返回空格
+pub fn detect_delimiter(text: &str) -> char {
+    let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).take(SAMPLE_LINES).collect();
+    let mut best = (' ', 0);
+    for delimiter in DELIMITERS {
+        let count = lines.iter().filter(|line| count_outside_quotes(line, delimiter) > 0).count();
+        if count > best.1 {
+            best = (delimiter, count);
+        }
+    }
+    best.0
+}
+
+fn is_number(cell: &str) -> bool {
+    cell.parse::<f64>().is_ok()
+}
+
+/// 导入时某一列写入的位置
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColumnTarget {
+    Ignore,
+    /// 固定的列，如 "学号"
+    Field(&'static str),
+    /// 分项列，按出现顺序编号，如 "作业" 依次写入 作业1, 作业2…
+    Items(&'static str),
+}
+
+impl ColumnTarget {
+    pub fn label(&self) -> String {
+        match self {
+            ColumnTarget::Ignore => "忽略".to_string(),
+            ColumnTarget::Field(field) => field.to_string(),
+            ColumnTarget::Items(prefix) => format!("{}(分项)", prefix),
+        }
+    }
+
+    /// 表头是否对应该目标，如 "期末(40%)" 对应 期末，"讨论分项1" 对应 讨论(分项)
+    fn matches(&self, header: &str) -> bool {
+        let header = normalize_header(header.split('(').next().unwrap_or_default());
+        match self {
+            ColumnTarget::Ignore => false,
+            ColumnTarget::Field(field) => header == normalize_header(field),
+            ColumnTarget::Items(prefix) => header
+                .strip_prefix(&normalize_header(prefix))
+                .map(|rest| rest.trim_start_matches("分项"))
+                .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit())),
+        }
+    }
+}
+
+fn targets_of<T: TableRow>() -> Vec<ColumnTarget> {
+    let mut targets = vec![ColumnTarget::Ignore];
+    targets.extend(T::FIELDS.iter().map(|field| ColumnTarget::Field(field)));
+    targets.extend(
+        T::ITEM_COLUMNS
+            .iter()
+            .flat_map(|prefixes| prefixes.iter())
+            .map(|prefix| ColumnTarget::Items(prefix)),
+    );
+    targets
+}
+
+/// `table_type` 对应数据表可选的列
+pub fn column_targets(table_type: &TableType) -> Vec<ColumnTarget> {
+    match table_type {
+        TableType::FinalScore => targets_of::<FinalItem>(),
+        TableType::ProjectScore => targets_of::<ProjectItem>(),
+        TableType::GroupList => targets_of::<GroupItem>(),
+    }
+}
+
+/// 识别后的粘贴数据
+pub struct PastedTable {
+    pub delimiter: char,
+    /// 第一行是否为表头
+    pub has_header: bool,
+    /// 所有行(包括表头)，已补齐到相同列数
+    rows: Vec<Vec<String>>,
+}
+
+impl PastedTable {
+    /// 识别分隔符与表头。`targets` 中的列名出现在第一行时视为表头
+    pub fn parse(text: &str, targets: &[ColumnTarget]) -> Self {
+        let text = normalize_text(text);
+        let delimiter = detect_delimiter(&text);
+        let mut rows: Vec<Vec<String>> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| split_cells(line, delimiter))
+            .collect();
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(width, String::new());
+        }
+        let has_header = Self::detect_header(&rows, targets);
+        Self {
+            delimiter,
+            has_header,
+            rows,
+        }
+    }
+
+    /// 第一行含有已知列名，或第一行没有数字而后面的行在同一列有数字时，视为表头
+    fn detect_header(rows: &[Vec<String>], targets: &[ColumnTarget]) -> bool {
+        let Some(first) = rows.first() else {
+            return false;
+        };
+        if first.iter().any(|cell| targets.iter().any(|target| target.matches(cell))) {
+            return true;
+        }
+        let numeric_below = |col: usize| rows[1..].iter().take(SAMPLE_LINES).any(|row| is_number(&row[col]));
+        rows.len() > 1
+            && first.iter().all(|cell| !is_number(cell))
+            && (0..first.len()).any(numeric_below)
+    }
+
+    pub fn delimiter_label(&self) -> &'static str {
+        match self.delimiter {
+            '\t' => "制表符",
+            ',' => "逗号",
+            ';' => "分号",
+            '|' => "竖线",
+            _ => "空格",
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.len())
+    }
+
+    /// 各列的名称，没有表头时为 "第 N 列"
+    pub fn headers(&self) -> Vec<String> {
+        match (self.has_header, self.rows.first()) {
+            (true, Some(first)) => first.clone(),
+            _ => (1..=self.width()).map(|i| format!("第 {} 列", i)).collect(),
+        }
+    }
+
+    /// 除表头以外的数据行
+    pub fn data_rows(&self) -> &[Vec<String>] {
+        let skip = usize::from(self.has_header).min(self.rows.len());
+        &self.rows[skip..]
+    }
+
+    /// 按表头猜测每一列的目标；没有表头时按顺序对应固定的列
+    pub fn guess_mapping(&self, targets: &[ColumnTarget]) -> Vec<ColumnTarget> {
+        if !self.has_header {
+            let fields: Vec<ColumnTarget> = targets
+                .iter()
+                .filter(|target| matches!(target, ColumnTarget::Field(_)))
+                .copied()
+                .collect();
+            return (0..self.width())
+                .map(|col| fields.get(col).copied().unwrap_or(ColumnTarget::Ignore))
+                .collect();
+        }
+        let mut used = vec![];
+        self.headers()
+            .iter()
+            .map(|header| {
+                let target = targets
+                    .iter()
+                    .copied()
+                    .find(|target| target.matches(header) && !used.contains(target))
+                    .unwrap_or(ColumnTarget::Ignore);
+                // 固定的列只对应一次，分项列可以对应多次
+                if matches!(target, ColumnTarget::Field(_)) {
+                    used.push(target);
+                }
+                target
+            })
+            .collect()
+    }
+
+    /// 按 `mapping` 生成带表头的输入区格式，忽略的列不输出。
+    /// 各列按 `targets` 中的顺序排列，保证第一列为学号或组号
+    pub fn to_text(&self, mapping: &[ColumnTarget], targets: &[ColumnTarget]) -> String {
+        let mut mapped: Vec<(usize, ColumnTarget)> = mapping
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, target)| *target != ColumnTarget::Ignore)
+            .collect();
+        mapped.sort_by_key(|(_, target)| targets.iter().position(|t| t == target));
+
+        let mut columns = vec![];
+        let mut headers = vec![];
+        let mut item_counts: HashMap<&str, usize> = HashMap::new();
+        for (col, target) in mapped {
+            let header = match target {
+                ColumnTarget::Ignore => continue,
+                ColumnTarget::Field(field) => field.to_string(),
+                ColumnTarget::Items(prefix) => {
+                    let count = item_counts.entry(prefix).or_default();
+                    *count += 1;
+                    format!("{}{}", prefix, count)
+                }
+            };
+            columns.push(col);
+            headers.push(header);
+        }
+
+        let mut writer = csv::WriterBuilder::new().from_writer(vec![]);
+        let _ = writer.write_record(&headers);
+        for row in self.data_rows() {
+            let _ = writer.write_record(columns.iter().map(|&col| row[col].as_str()));
+        }
+        let data = writer.into_inner().unwrap_or_default();
+        String::from_utf8(data).unwrap_or_default()
+    }
+}
+
+/// 等待用户确认列对应关系的粘贴数据
+pub struct PasteImport {
+    pub table_type: TableType,
+    pub table: PastedTable,
+    pub targets: Vec<ColumnTarget>,
+    /// 每一列写入的位置
+    pub mapping: Vec<ColumnTarget>,
+}
+
+impl PasteImport {
+    pub fn new(table_type: TableType, text: &str) -> Self {
+        let targets = column_targets(&table_type);
+        let table = PastedTable::parse(text, &targets);
+        let mapping = table.guess_mapping(&targets);
+        Self {
+            table_type,
+            table,
+            targets,
+            mapping,
+        }
+    }
+
+    /// 修改第一行是否为表头，并重新猜测每一列的目标
+    pub fn set_header(&mut self, has_header: bool) {
+        self.table.has_header = has_header;
+        self.mapping = self.table.guess_mapping(&self.targets);
+    }
+
+    /// 学号或组号是否已对应到某一列
+    pub fn has_key(&self) -> bool {
+        self.targets.get(1).is_some_and(|key| self.mapping.contains(key))
+    }
+
+    pub fn to_text(&self) -> String {
+        self.table.to_text(&self.mapping, &self.targets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::split_to_table;
+
+    fn final_targets() -> Vec<ColumnTarget> {
+        column_targets(&TableType::FinalScore)
+    }
+
+    #[test]
+    fn normalize_text_converts_full_width_punctuation() {
+        assert_eq!(normalize_text("\u{feff}学号，姓名\r\n１２\u{3000}“张三”"), "学号,姓名\n12 \"张三\"");
+    }
+
+    #[test]
+    fn detects_tab_comma_and_space_delimiters() {
+        assert_eq!(detect_delimiter("学号\t姓名\n1\t张三"), '\t');
+        assert_eq!(detect_delimiter(&normalize_text("学号，姓名\n1，张三")), ',');
+        assert_eq!(detect_delimiter("1 张三  80\n2 李四 90"), ' ');
+        // 引号内的逗号不算分隔符
+        assert_eq!(detect_delimiter("\"a,b\" c\n\"d,e\" f\ng;h"), ';');
+    }
+
+    #[test]
+    fn split_line_handles_quotes_and_whitespace() {
+        let cells = split_line(r#""Zhang, San", 80 ,"say ""hi""""#, ',');
+        assert_eq!(cells, ["Zhang, San", "80", "say \"hi\""]);
+        assert_eq!(split_cells("1  张三 \t 80", ' '), ["1", "张三", "80"]);
+    }
+
+    #[test]
+    fn split_to_table_keeps_empty_lines() {
+        let table = split_to_table("1\t张三\n\n2\t李四".to_string());
+        assert_eq!(table, vec![vec!["1", "张三"], vec![""], vec!["2", "李四"]]);
+        let table = split_to_table("1，张三，80".to_string());
+        assert_eq!(table, vec![vec!["1", "张三", "80"]]);
+    }
+
+    #[test]
+    fn detects_known_and_numeric_headers() {
+        let targets = final_targets();
+        assert!(PastedTable::parse("学号\t姓名\t期末(40%)\n1\t张三\t80", &targets).has_header);
+        assert!(PastedTable::parse("编号,名字\n1,张三", &targets).has_header);
+        assert!(!PastedTable::parse("1,张三\n2,李四", &targets).has_header);
+        assert!(!PastedTable::parse("张三,李四\n王五,赵六", &targets).has_header);
+    }
+
+    #[test]
+    fn guesses_mapping_and_builds_input_text() {
+        let targets = final_targets();
+        let table = PastedTable::parse("姓名\t学号\t备注\t作业1\t作业2\n张三\t1\tx\t9\t8", &targets);
+        let mapping = table.guess_mapping(&targets);
+        assert!(
+            mapping
+                == [
+                    ColumnTarget::Field("姓名"),
+                    ColumnTarget::Field("学号"),
+                    ColumnTarget::Ignore,
+                    ColumnTarget::Items("作业"),
+                    ColumnTarget::Items("作业"),
+                ]
+        );
+        assert_eq!(table.to_text(&mapping, &targets), "学号,姓名,作业1,作业2\n1,张三,9,8\n");
+
+        // 没有表头时按顺序对应固定的列
+        let table = PastedTable::parse("1 张三", &targets);
+        assert!(table.guess_mapping(&targets) == [ColumnTarget::Field("学号"), ColumnTarget::Field("姓名")]);
+    }
+}
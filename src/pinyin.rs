@@ -0,0 +1,74 @@
+//! 汉字拼音首字母，用于按姓名首字母搜索学生。
+//!
+//! GB2312 一级汉字(3755 个常用字)按拼音排序，每个声母对应一段连续的编码，
+//! 因此只需比较编码即可得到首字母。二级汉字按部首排序，无法得到首字母，会被忽略。
+
+use encoding_rs::GBK;
+
+/// 各首字母在 GB2312 中的起始编码，按编码升序排列
+const INITIALS: [(u16, char); 23] = [
+    (0xB0A1, 'a'),
+    (0xB0C5, 'b'),
+    (0xB2C1, 'c'),
+    (0xB4EE, 'd'),
+    (0xB6EA, 'e'),
+    (0xB7A2, 'f'),
+    (0xB8C1, 'g'),
+    (0xB9FE, 'h'),
+    (0xBBF7, 'j'),
+    (0xBFA6, 'k'),
+    (0xC0AC, 'l'),
+    (0xC2E8, 'm'),
+    (0xC4C3, 'n'),
+    (0xC5B6, 'o'),
+    (0xC5BE, 'p'),
+    (0xC6DA, 'q'),
+    (0xC8BB, 'r'),
+    (0xC8F6, 's'),
+    (0xCBFA, 't'),
+    (0xCDDA, 'w'),
+    (0xCEF4, 'x'),
+    (0xD1B9, 'y'),
+    (0xD4D1, 'z'),
+];
+/// GB2312 一级汉字的最后一个编码
+const LEVEL1_END: u16 = 0xD7F9;
+
+/// 单个汉字的拼音首字母，非一级汉字返回 None
+pub fn initial(c: char) -> Option<char> {
+    let mut buffer = [0u8; 4];
+    let (bytes, _, had_errors) = GBK.encode(c.encode_utf8(&mut buffer));
+    if had_errors || bytes.len() != 2 {
+        return None;
+    }
+    let code = u16::from_be_bytes([bytes[0], bytes[1]]);
+    if !(INITIALS[0].0..=LEVEL1_END).contains(&code) {
+        return None;
+    }
+    INITIALS.iter().rev().find(|(start, _)| code >= *start).map(|(_, letter)| *letter)
+}
+
+/// 文本的拼音首字母，如 "张三" 为 "zs"；字母与数字转为小写保留，其他字符忽略
+pub fn initials(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            c if c.is_ascii_alphanumeric() => Some(c.to_ascii_lowercase()),
+            c => initial(c),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initials_of_common_names() {
+        assert_eq!(initials("张三"), "zs");
+        assert_eq!(initials("欧阳娜娜"), "oynn");
+        assert_eq!(initials("李 Lei 2"), "llei2");
+        assert_eq!(initial('啊'), Some('a'));
+        assert_eq!(initial('座'), Some('z'));
+        assert_eq!(initial('-'), None);
+    }
+}
@@ -1,24 +1,20 @@
 use std::{collections::HashSet, sync::{Arc, Mutex}};
 
-use crate::utils::{split_to_table, vec_to_string};
-
-use super::app_state::*;
+use ads::app_state::*;
+use ads::paste::{column_targets, PastedTable};
+use ads::sync::sync_project_scores;
+use ads::table_io::table_to_text;
 
 pub fn build_final_from_input(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
     ui.horizontal(|ui| {
         if ui.button("从名单(学号，成绩)构造成绩表").clicked() {
-            let input_text = {
-                let state = state.lock().unwrap();
-                state.input_text.clone()
-            };
-
-            let table = split_to_table(input_text);
-            let new_text: Vec<Vec<String>> = table.iter().map(|row| {
-                FinalItem::from_id_name(row).to_vec()
-            }).collect();
-            let new_text = vec_to_string(new_text);
             let mut state = state.lock().unwrap();
-            state.input_text = new_text;
+            let table = PastedTable::parse(&state.input_text, &column_targets(&TableType::FinalScore));
+            let rows: Vec<FinalItem> = table.data_rows().iter().map(|row| {
+                FinalItem::from_id_name(row)
+            }).collect();
+            let new_text = table_to_text(&rows);
+            state.edit("从名单构造成绩表", |state| state.input_text = new_text);
         }
     });
 }
@@ -26,18 +22,13 @@ pub fn build_final_from_input(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
 pub fn build_group_from_input(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
     ui.horizontal(|ui| {
         if ui.button("组队名单(id 学号1 姓名1...)格式转换").clicked() {
-            let input_text = {
-                let state = state.lock().unwrap();
-                state.input_text.clone()
-            };
-
-            let table = split_to_table(input_text);
-            let new_text: Vec<Vec<String>> = table.iter().map(|row| {
-                GroupItem::from_raw(row).to_vec()
-            }).collect();
-            let new_text = vec_to_string(new_text);
             let mut state = state.lock().unwrap();
-            state.input_text = new_text;
+            let table = PastedTable::parse(&state.input_text, &column_targets(&TableType::GroupList));
+            let rows: Vec<GroupItem> = table.data_rows().iter().map(|row| {
+                GroupItem::from_raw(row)
+            }).collect();
+            let new_text = table_to_text(&rows);
+            state.edit("组队名单格式转换", |state| state.input_text = new_text);
         }
     });
 }
@@ -45,19 +36,17 @@ pub fn build_group_from_input(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
 pub fn build_project_from_group(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
     ui.horizontal(|ui| {
         if ui.button("从组队名单构造 Project 表").clicked() {
-            let group_num = {
+            let (group_num, rules) = {
                 let state = state.lock().unwrap();
-                state.group_table.len()
+                (state.group_table.len(), state.project_rules.clone())
             };
 
-            let new_text: Vec<Vec<String>> = (1..=group_num).map(|id: usize| {
-                let mut item = ProjectItem::default();
-                item.group_id = id;
-                item.to_vec()
+            let rows: Vec<ProjectItem> = (1..=group_num).map(|id: usize| {
+                ProjectItem::new(id, &rules)
             }).collect();
-            let new_text = vec_to_string(new_text);
+            let new_text = table_to_text(&rows);
             let mut state = state.lock().unwrap();
-            state.input_text = new_text;
+            state.edit("从组队名单构造 Project 表", |state| state.input_text = new_text);
         }
     });
 }
@@ -89,7 +78,24 @@ pub fn find_student_no_group(stat: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
             }).collect();
             let new_text = new_text.join(",");
             let mut state = stat.lock().unwrap();
-            state.output_text.push(Log::new("筛选未组队同学".to_string(), new_text));
+            state.log("筛选未组队同学".to_string(), new_text);
+        }
+    });
+}
+pub fn sync_project_to_final(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        if ui.button("同步 Project 成绩").clicked() {
+            let mut state = state.lock().unwrap();
+            let report = state.edit("同步 Project 成绩", |state| {
+                let AppState {
+                    final_table,
+                    project_table,
+                    group_table,
+                    ..
+                } = state;
+                sync_project_scores(final_table, project_table, group_table)
+            });
+            state.log("同步 Project 成绩".to_string(), report.summary());
         }
     });
-}
\ No newline at end of file
+}
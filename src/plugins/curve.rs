@@ -0,0 +1,180 @@
+use std::sync::{Arc, Mutex};
+
+use ads::app_state::*;
+use ads::curve::{adjusted, check, propose, Curve};
+use ads::grade::{Distribution, GradeBand};
+
+/// 逐人预览区域的最大高度
+const PREVIEW_HEIGHT: f32 = 200.0;
+
+/// 以百分数编辑 0 到 1 的比例
+fn rate_value(ui: &mut egui::Ui, rate: &mut f32) {
+    let mut percent = *rate * 100.0;
+    if ui
+        .add(egui::DragValue::new(&mut percent).speed(0.5).range(0.0..=100.0).suffix("%"))
+        .changed()
+    {
+        *rate = percent / 100.0;
+    }
+}
+
+fn count_label(distribution: &Distribution, band: GradeBand) -> String {
+    format!("{} ({:.1}%)", distribution.count(band), distribution.rate(band) * 100.0)
+}
+
+/// 要求的检查结果，满足时显示为绿色
+fn check_label(ui: &mut egui::Ui, problems: &[String]) {
+    if problems.is_empty() {
+        ui.colored_label(egui::Color32::DARK_GREEN, "满足优秀率与及格率要求");
+    }
+    for problem in problems {
+        ui.colored_label(ui.visuals().error_fg_color, problem);
+    }
+}
+
+/// 曲线参数
+fn curve_params(ui: &mut egui::Ui, curve: &mut Curve) {
+    match curve {
+        Curve::Linear { shift } => {
+            ui.label("加分");
+            ui.add(egui::DragValue::new(shift).speed(0.1).max_decimals(2));
+        }
+        Curve::Power { exponent } => {
+            ui.label("指数(0.5 即开方乘十)");
+            ui.add(egui::DragValue::new(exponent).speed(0.005).range(0.05..=2.0).max_decimals(3));
+        }
+        Curve::Piecewise {
+            pass_from,
+            excellent_from,
+        } => {
+            ui.label("原始分");
+            ui.add(egui::DragValue::new(pass_from).speed(0.1).range(0.01..=100.0).max_decimals(2));
+            ui.label("记为及格，");
+            ui.add(egui::DragValue::new(excellent_from).speed(0.1).range(0.01..=100.0).max_decimals(2));
+            ui.label("记为优秀");
+        }
+    }
+}
+
+/// 等级分布检查与调分：按要求生成曲线建议，预览每名学生的结果后写入调整后总分
+pub fn curve_ui(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
+    egui::CollapsingHeader::new("等级分布与调分").show(ui, |ui| {
+        let mut state = state.lock().unwrap();
+        if state.final_table.is_empty() {
+            ui.label("总评成绩表为空");
+            return;
+        }
+        let before = state.curve.clone();
+
+        ui.horizontal(|ui| {
+            ui.label("优秀率上限");
+            rate_value(ui, &mut state.curve.max_excellent_rate);
+            ui.label("及格率下限");
+            rate_value(ui, &mut state.curve.min_pass_rate);
+        });
+
+        let policy = &state.grading_policy;
+        let draft = state.curve.draft;
+        let raw = Distribution::of(state.final_table.iter().map(|row| row.final_score));
+        let preview = Distribution::of(state.final_table.iter().map(|row| adjusted(row, policy, &draft)));
+        egui::Grid::new("curve_distribution").striped(true).show(ui, |ui| {
+            for title in ["等级", "分数段", "原始总分", "调分预览"] {
+                ui.strong(title);
+            }
+            ui.end_row();
+            for band in GradeBand::ALL {
+                ui.label(band.label());
+                ui.label(band.range_label());
+                ui.label(count_label(&raw, band));
+                ui.label(count_label(&preview, band));
+                ui.end_row();
+            }
+        });
+        ui.label("原始总分:");
+        check_label(ui, &check(&raw, &state.curve));
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("曲线");
+            let kinds = [
+                Curve::Linear { shift: 0.0 },
+                Curve::Power { exponent: 1.0 },
+                Curve::Piecewise {
+                    pass_from: 60.0,
+                    excellent_from: 85.0,
+                },
+            ];
+            for kind in kinds {
+                let selected = std::mem::discriminant(&draft) == std::mem::discriminant(&kind);
+                if ui.selectable_label(selected, kind.label()).clicked() && !selected {
+                    state.curve.draft = propose(&state, &kind);
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            curve_params(ui, &mut state.curve.draft);
+            if ui.button("按要求生成").on_hover_text("求出满足要求且改动最小的参数").clicked() {
+                state.curve.draft = propose(&state, &draft);
+            }
+        });
+        ui.label("调分预览:");
+        check_label(ui, &check(&preview, &state.curve));
+
+        egui::CollapsingHeader::new("逐人预览").show(ui, |ui| {
+            egui::ScrollArea::vertical().max_height(PREVIEW_HEIGHT).show(ui, |ui| {
+                egui::Grid::new("curve_preview").striped(true).show(ui, |ui| {
+                    for title in ["学号", "姓名", "原始总分", "调分后", "等级"] {
+                        ui.strong(title);
+                    }
+                    ui.end_row();
+                    for row in &state.final_table {
+                        let score = adjusted(row, &state.grading_policy, &draft);
+                        let (before, after) = (GradeBand::of(row.final_score), GradeBand::of(score));
+                        ui.label(&row.student_info.student_id);
+                        ui.label(&row.student_info.student_name);
+                        ui.label(format!("{:.2}", row.final_score));
+                        ui.label(format!("{:.2}", score));
+                        if before == after {
+                            ui.label(after.label());
+                        } else {
+                            ui.strong(format!("{} → {}", before.label(), after.label()));
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+
+        ui.horizontal(|ui| {
+            let draft = state.curve.draft;
+            if ui
+                .add_enabled(draft.is_valid(), egui::Button::new("应用到调整后总分"))
+                .on_hover_text("原始总分保持不变，调分结果写入单独的一列")
+                .clicked()
+            {
+                state.edit("应用调分", |state| {
+                    state.curve.applied = Some(draft);
+                    state.update_reported_scores();
+                });
+                state.log("调分".to_string(), format!("已应用: {}", draft));
+            }
+            if ui
+                .add_enabled(state.curve.applied.is_some(), egui::Button::new("取消调分"))
+                .clicked()
+            {
+                state.edit("取消调分", |state| {
+                    state.curve.applied = None;
+                    state.update_reported_scores();
+                });
+                state.log("调分".to_string(), "已清除调整后总分".to_string());
+            }
+        });
+        match state.curve.applied {
+            Some(curve) => ui.label(format!("当前已应用: {}", curve)),
+            None => ui.label("当前未调分"),
+        };
+        if state.curve != before {
+            state.mark_dirty();
+        }
+    });
+}
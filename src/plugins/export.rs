@@ -0,0 +1,29 @@
+use std::sync::{Arc, Mutex};
+
+use ads::app_state::*;
+use ads::export::{export_xlsx, DEFAULT_EXPORT_PATH};
+
+pub fn export_excel(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        let clicked = ui.button("导出 Excel").clicked();
+        let mut state = state.lock().unwrap();
+        let path = egui::TextEdit::singleline(&mut state.export_path)
+            .hint_text(DEFAULT_EXPORT_PATH)
+            .desired_width(200.0)
+            .show(ui);
+        if path.response.changed() {
+            state.mark_dirty();
+        }
+        if clicked {
+            let path = match state.export_path.trim() {
+                "" => DEFAULT_EXPORT_PATH.to_string(),
+                path => path.to_string(),
+            };
+            let message = match export_xlsx(&state, &path) {
+                Ok(()) => format!("已导出到 {}", path),
+                Err(e) => format!("导出失败: {}", e),
+            };
+            state.log("导出 Excel".to_string(), message);
+        }
+    });
+}
@@ -0,0 +1,82 @@
+use std::sync::{Arc, Mutex};
+
+use ads::app_state::*;
+
+fn log_undo(state: &mut AppState, action: &str, label: Option<String>) {
+    if let Some(label) = label {
+        state.log(action.to_string(), label);
+    }
+}
+
+/// Ctrl+Z 撤销，Ctrl+Y / Ctrl+Shift+Z 重做。文本框获得焦点时交给文本框自己处理
+pub fn history_shortcuts(state: Arc<Mutex<AppState>>, ctx: &egui::Context) {
+    if ctx.memory(|memory| memory.focused().is_some()) {
+        return;
+    }
+    let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+    let redo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+    let redo_shift = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+    // 先检查带 Shift 的组合，避免被 Ctrl+Z 抢先匹配
+    let (redo_pressed, undo_pressed) = ctx.input_mut(|input| {
+        let redo_pressed = input.consume_shortcut(&redo_shift) || input.consume_shortcut(&redo);
+        (redo_pressed, input.consume_shortcut(&undo))
+    });
+    let mut state = state.lock().unwrap();
+    if undo_pressed {
+        let label = state.undo();
+        log_undo(&mut state, "撤销", label);
+    }
+    if redo_pressed {
+        let label = state.redo();
+        log_undo(&mut state, "重做", label);
+    }
+}
+
+pub fn history_ui(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
+    egui::CollapsingHeader::new("历史记录").show(ui, |ui| {
+        let mut state = state.lock().unwrap();
+        ui.horizontal(|ui| {
+            let can_undo = !state.history.undo_entries().is_empty();
+            let can_redo = !state.history.redo_entries().is_empty();
+            if ui.add_enabled(can_undo, egui::Button::new("撤销 (Ctrl+Z)")).clicked() {
+                let label = state.undo();
+                log_undo(&mut state, "撤销", label);
+            }
+            if ui.add_enabled(can_redo, egui::Button::new("重做 (Ctrl+Y)")).clicked() {
+                let label = state.redo();
+                log_undo(&mut state, "重做", label);
+            }
+        });
+
+        // 点击某条记录时撤销或重做到该记录为止
+        let mut undo_steps = 0;
+        let mut redo_steps = 0;
+        egui::ScrollArea::vertical()
+            .id_source("history_list")
+            .max_height(150.0)
+            .show(ui, |ui| {
+                let redo_entries = state.history.redo_entries();
+                for (index, entry) in redo_entries.iter().enumerate() {
+                    let text = format!("{} {} (已撤销)", entry.time.format("%H:%M:%S"), entry.label);
+                    if ui.selectable_label(false, egui::RichText::new(text).weak()).clicked() {
+                        redo_steps = redo_entries.len() - index;
+                    }
+                }
+                let undo_entries = state.history.undo_entries();
+                for (index, entry) in undo_entries.iter().enumerate().rev() {
+                    let text = format!("{} {}", entry.time.format("%H:%M:%S"), entry.label);
+                    if ui.selectable_label(index + 1 == undo_entries.len(), text).clicked() {
+                        undo_steps = undo_entries.len() - index - 1;
+                    }
+                }
+            });
+        for _ in 0..undo_steps {
+            let label = state.undo();
+            log_undo(&mut state, "撤销", label);
+        }
+        for _ in 0..redo_steps {
+            let label = state.redo();
+            log_undo(&mut state, "重做", label);
+        }
+    });
+}
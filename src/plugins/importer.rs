@@ -0,0 +1,77 @@
+use std::sync::{Arc, Mutex};
+
+use ads::app_state::*;
+use ads::importer::{import_item_scores, import_project_scores, read_sheet};
+
+pub fn import_item_scores_ui(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        let clicked = ui.button("导入作业/讨论成绩").clicked();
+        let mut state = state.lock().unwrap();
+        let (path, target, item_index) = (state.import_path.clone(), state.import_target, state.import_item_index);
+        egui::TextEdit::singleline(&mut state.import_path)
+            .hint_text("PTA 导出的 .csv/.xlsx 路径")
+            .desired_width(200.0)
+            .show(ui);
+        egui::ComboBox::from_id_source("import_score_target")
+            .selected_text(state.import_target.label())
+            .show_ui(ui, |ui| {
+                for target in [ScoreTarget::Homework, ScoreTarget::Discussion] {
+                    ui.selectable_value(&mut state.import_target, target, target.label());
+                }
+            });
+        ui.add(egui::DragValue::new(&mut state.import_item_index).range(0..=50))
+            .on_hover_text("0: 按列导入全部分项; N: 将 \"总分\" 列写入第 N 项");
+        if (path, target, item_index) != (state.import_path.clone(), state.import_target, state.import_item_index) {
+            state.mark_dirty();
+        }
+        if clicked {
+            let path = state.import_path.trim().to_string();
+            let (target, item_index) = (state.import_target, state.import_item_index);
+            let label = format!("导入{}成绩", target.label());
+            let message = match read_sheet(&path).and_then(|sheet| {
+                state.edit(&label, |state| import_item_scores(&mut state.final_table, &sheet, target, item_index))
+            }) {
+                Ok(report) => report.summary(),
+                Err(e) => format!("导入失败: {}", e),
+            };
+            state.log(format!("导入{}成绩 - {}", target.label(), path), message);
+        }
+    });
+}
+
+pub fn import_project_scores_ui(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        let clicked = ui.button("导入 Project 成绩").clicked();
+        let mut state = state.lock().unwrap();
+        let report = egui::TextEdit::singleline(&mut state.project_report_path)
+            .hint_text("报告评分表路径")
+            .desired_width(150.0)
+            .show(ui);
+        let pr = egui::TextEdit::singleline(&mut state.project_pr_path)
+            .hint_text("PR 互评表路径")
+            .desired_width(150.0)
+            .show(ui);
+        if report.response.changed() || pr.response.changed() {
+            state.mark_dirty();
+        }
+        if clicked {
+            let sources = [
+                (ProjectScoreTarget::Report, state.project_report_path.trim().to_string()),
+                (ProjectScoreTarget::Pr, state.project_pr_path.trim().to_string()),
+            ];
+            for (target, path) in sources {
+                if path.is_empty() {
+                    continue;
+                }
+                let label = format!("导入{}", target.label());
+                let message = match read_sheet(&path).and_then(|sheet| {
+                    state.edit(&label, |state| import_project_scores(&mut state.project_table, &sheet, target))
+                }) {
+                    Ok(report) => report.summary(),
+                    Err(e) => format!("导入失败: {}", e),
+                };
+                state.log(format!("导入{} - {}", target.label(), path), message);
+            }
+        }
+    });
+}
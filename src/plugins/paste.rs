@@ -0,0 +1,99 @@
+use std::sync::{Arc, Mutex};
+
+use ads::app_state::*;
+use ads::paste::PasteImport;
+use ads::table_io::preview_import;
+
+/// 每列显示的示例数据行数
+const SAMPLE_ROWS: usize = 3;
+
+/// "识别导入"：识别输入区中粘贴的表格，确认每一列对应的字段后再导入
+pub fn paste_ui(state: &mut AppState, ui: &mut egui::Ui) {
+    if ui
+        .button("识别导入")
+        .on_hover_text("自动识别分隔符、表头与全角标点，确认各列对应的字段后导入当前表")
+        .clicked()
+    {
+        state.paste_import = Some(PasteImport::new(state.table_type.clone(), &state.input_text));
+    }
+}
+
+/// 列对应关系窗口，确认后生成导入预览或整理输入区
+pub fn paste_window(state: Arc<Mutex<AppState>>, ctx: &egui::Context) {
+    let mut state = state.lock().unwrap();
+    let Some(paste) = &mut state.paste_import else {
+        return;
+    };
+    let mut previewed = false;
+    let mut rewritten = false;
+    let mut cancelled = false;
+    egui::Window::new("识别导入")
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "分隔符: {}，共 {} 列 {} 行",
+                paste.table.delimiter_label(),
+                paste.table.width(),
+                paste.table.data_rows().len()
+            ));
+            let mut has_header = paste.table.has_header;
+            if ui.checkbox(&mut has_header, "第一行为表头").changed() {
+                paste.set_header(has_header);
+            }
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                egui::Grid::new("paste_mapping").striped(true).show(ui, |ui| {
+                    ui.strong("列");
+                    ui.strong("对应字段");
+                    ui.strong("示例");
+                    ui.end_row();
+                    let headers = paste.table.headers();
+                    for (col, header) in headers.iter().enumerate() {
+                        ui.label(header);
+                        egui::ComboBox::from_id_source(("paste_target", col))
+                            .selected_text(paste.mapping[col].label())
+                            .show_ui(ui, |ui| {
+                                for target in &paste.targets {
+                                    ui.selectable_value(&mut paste.mapping[col], *target, target.label());
+                                }
+                            });
+                        let samples: Vec<&str> = paste
+                            .table
+                            .data_rows()
+                            .iter()
+                            .take(SAMPLE_ROWS)
+                            .map(|row| row[col].as_str())
+                            .collect();
+                        ui.label(samples.join(", "));
+                        ui.end_row();
+                    }
+                });
+            });
+            if !paste.has_key() {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("请选择{}所在的列", paste.targets[1].label()),
+                );
+            }
+            ui.horizontal(|ui| {
+                previewed = ui.add_enabled(paste.has_key(), egui::Button::new("预览导入")).clicked();
+                rewritten = ui
+                    .button("写入输入区")
+                    .on_hover_text("将整理后的数据写入输入区，不修改数据表")
+                    .clicked();
+                cancelled = ui.button("取消").clicked();
+            });
+        });
+    if previewed || rewritten {
+        if let Some(paste) = state.paste_import.take() {
+            let text = paste.to_text();
+            if previewed {
+                let preview = preview_import(&state, paste.table_type, &text);
+                state.import_preview = Some(preview);
+            } else {
+                state.edit("整理粘贴数据", |state| state.input_text = text);
+            }
+        }
+    } else if cancelled {
+        state.paste_import = None;
+    }
+}
@@ -0,0 +1,333 @@
+use std::sync::{Arc, Mutex};
+
+use ads::app_state::*;
+use ads::assignment::AssignmentPool;
+use ads::deadline::{format_time, parse_time};
+use chrono::{DateTime, Utc};
+use ads::grade::{GradeLevel, GradePoint, GradeScale};
+
+pub fn grading_policy_ui(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
+    egui::CollapsingHeader::new("总评计算规则").show(ui, |ui| {
+        let mut state = state.lock().unwrap();
+        let before = state.grading_policy.clone();
+        let policy = &mut state.grading_policy;
+        egui::Grid::new("grading_policy_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("讨论分 = 讨论平均分 ×").on_hover_text("未填写讨论分项信息时使用");
+                ui.add(egui::DragValue::new(&mut policy.discussion_multiplier).speed(0.1));
+                ui.end_row();
+
+                ui.label("作业分 = 作业总分 ÷").on_hover_text("未填写作业分项信息时使用");
+                ui.add(egui::DragValue::new(&mut policy.homework_divisor).speed(0.1).range(0.1..=f32::MAX));
+                ui.end_row();
+
+                ui.label("期中分 = max(期末, 期中) ÷");
+                ui.add(egui::DragValue::new(&mut policy.midterm_divisor).speed(0.1).range(0.1..=f32::MAX));
+                ui.end_row();
+
+                ui.label("平时分上限");
+                ui.add(egui::DragValue::new(&mut policy.general_score_cap).speed(0.5));
+                ui.end_row();
+
+                ui.label("期末及格线(低于则总分为 0)");
+                ui.add(egui::DragValue::new(&mut policy.final_exam_threshold).speed(0.5).range(0.0..=100.0));
+                ui.end_row();
+
+                ui.label("期末占比(%)");
+                ui.add(egui::DragValue::new(&mut policy.final_exam_weight).speed(0.5).range(0.0..=100.0));
+                ui.end_row();
+
+                ui.label("未提交的作业/讨论");
+                ui.checkbox(&mut policy.missing_as_zero, "按 0 分计入")
+                    .on_hover_text("不勾选时与豁免一样不计入平均");
+                ui.end_row();
+            });
+
+        let discussion_count = state.final_table.iter().map(|row| row.discussion_score.len()).max().unwrap_or(0);
+        let homework_count = state.final_table.iter().map(|row| row.homework_score.len()).max().unwrap_or(0);
+        let policy = &mut state.grading_policy;
+        assignment_pool_ui(ui, "讨论分项", "讨论", &mut policy.discussion, discussion_count, false);
+        assignment_pool_ui(ui, "作业分项", "作业", &mut policy.homework, homework_count, true);
+        egui::CollapsingHeader::new("迟交扣分").show(ui, |ui| {
+            let late = &mut policy.late_penalty;
+            ui.label("作业分项填写截止时间后，按导入的提交时间扣分，不足一天按一天计");
+            if !policy.homework.weighted {
+                ui.label("未勾选加权折算时，作业分项只提供截止时间，作业分仍按上面的除数计算");
+            }
+            ui.horizontal(|ui| {
+                ui.label("每天扣");
+                ui.add(egui::DragValue::new(&mut late.percent_per_day).speed(0.5).range(0.0..=100.0).suffix("%"));
+                ui.label("最多扣");
+                ui.add(egui::DragValue::new(&mut late.max_percent).speed(0.5).range(0.0..=100.0).suffix("%"));
+                ui.label("宽限");
+                ui.add(egui::DragValue::new(&mut late.grace_minutes).range(0..=10080).suffix(" 分钟"));
+            });
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("恢复默认规则").clicked() {
+                state.grading_policy = GradingPolicy::default();
+            }
+            if ui.button("按当前规则重新计算总评").clicked() {
+                state.edit("按规则重新计算总评", |state| state.calculate_final_table());
+                state.log(
+                    "总评计算规则".to_string(),
+                    "已按当前规则重新计算总评成绩".to_string(),
+                );
+            }
+        });
+        if state.grading_policy != before {
+            state.mark_dirty();
+        }
+    });
+}
+
+pub fn project_rules_ui(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
+    egui::CollapsingHeader::new("Project 计算规则").show(ui, |ui| {
+        let mut state = state.lock().unwrap();
+        let before = state.project_rules.clone();
+        let rules = &mut state.project_rules;
+        egui::Grid::new("project_rules_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("展示组报告占比");
+                ui.add(egui::DragValue::new(&mut rules.presentation_report_weight).speed(0.01).range(0.0..=1.0));
+                ui.end_row();
+
+                ui.label("其余报告最高分占比");
+                ui.add(egui::DragValue::new(&mut rules.best_report_weight).speed(0.01).range(0.0..=1.0));
+                ui.end_row();
+
+                ui.label("剩余报告 Bonus = 分数之和 ÷");
+                ui.add(egui::DragValue::new(&mut rules.bonus_divisor).speed(0.1).range(0.1..=f32::MAX));
+                ui.end_row();
+
+                ui.label("PR 总分 = PR 平均分 ÷");
+                ui.add(egui::DragValue::new(&mut rules.pr_divisor).speed(0.1).range(0.1..=f32::MAX));
+                ui.end_row();
+
+                ui.label("每组报告数量");
+                ui.add(egui::DragValue::new(&mut rules.report_count).range(1..=20));
+                ui.end_row();
+
+                ui.label("每组 PR 评审人数");
+                ui.add(egui::DragValue::new(&mut rules.pr_reviewer_count).range(1..=20));
+                ui.end_row();
+
+                ui.label("每份报告满分");
+                ui.add(egui::DragValue::new(&mut rules.report_full_mark).speed(0.5).range(0.0..=f32::MAX));
+                ui.end_row();
+
+                ui.label("每次 PR 评审满分");
+                ui.add(egui::DragValue::new(&mut rules.pr_full_mark).speed(0.5).range(0.0..=f32::MAX));
+                ui.end_row();
+
+                ui.label("展示分数满分");
+                ui.add(egui::DragValue::new(&mut rules.presentation_full_mark).speed(0.5).range(0.0..=f32::MAX));
+                ui.end_row();
+
+                ui.label("未提交的报告/PR");
+                ui.checkbox(&mut rules.missing_as_zero, "按 0 分计入")
+                    .on_hover_text("不勾选时与豁免一样不计入");
+                ui.end_row();
+            });
+
+        ui.horizontal(|ui| {
+            if ui.button("恢复默认规则").clicked() {
+                state.project_rules = ProjectRules::default();
+            }
+            if ui.button("按当前规则重新计算 Project").clicked() {
+                state.edit("按规则重新计算 Project", |state| state.calculate_project_table());
+                state.log(
+                    "Project 计算规则".to_string(),
+                    "已按当前规则重新计算 Project 成绩".to_string(),
+                );
+            }
+        });
+        if state.project_rules != before {
+            state.mark_dirty();
+        }
+    });
+}
+
+/// 截止时间输入框的内容，留空表示没有截止时间，无法识别时为 None
+fn parse_deadline(text: &str) -> Option<Option<DateTime<Utc>>> {
+    match text.trim() {
+        "" => Some(None),
+        text => parse_time(text).map(Some),
+    }
+}
+
+/// 按课程时区编辑截止时间，按回车或失去焦点时保存；无法识别时以红色显示且不修改
+fn deadline_edit(ui: &mut egui::Ui, id: egui::Id, deadline: &mut Option<DateTime<Utc>>) {
+    let mut text = ui
+        .data_mut(|data| data.get_temp::<String>(id))
+        .unwrap_or_else(|| deadline.as_ref().map(format_time).unwrap_or_default());
+    let valid = parse_deadline(&text).is_some();
+    let mut edit = egui::TextEdit::singleline(&mut text)
+        .id(id)
+        .hint_text("2024-03-10 23:59")
+        .desired_width(120.0);
+    if !valid {
+        edit = edit.text_color(ui.visuals().error_fg_color);
+    }
+    let response = ui.add(edit).on_hover_text("北京时间，按回车或离开输入框后保存");
+    // 输入过程中的不完整时间不写入，避免每输入一个字符都修改规则
+    if response.lost_focus() {
+        if let Some(value) = parse_deadline(&text) {
+            *deadline = value;
+        }
+    }
+    // 编辑时保留输入的文本，失去焦点后按保存的值显示
+    if response.has_focus() {
+        ui.data_mut(|data| data.insert_temp(id, text));
+    } else {
+        ui.data_mut(|data| data.remove::<String>(id));
+    }
+}
+
+/// 一类分项的满分、权重与去掉最低分的规则，`count` 为总评表中该类分项的数量，
+/// `deadlines` 为真时可以填写截止时间
+fn assignment_pool_ui(
+    ui: &mut egui::Ui,
+    title: &str,
+    prefix: &str,
+    pool: &mut AssignmentPool,
+    count: usize,
+    deadlines: bool,
+) {
+    egui::CollapsingHeader::new(title).show(ui, |ui| {
+        ui.checkbox(&mut pool.weighted, "按分项信息加权折算")
+            .on_hover_text("勾选并填写分项信息后按各项得分率的加权平均折算到满分，否则按上面的规则计算");
+        ui.horizontal(|ui| {
+            ui.label("折算满分");
+            ui.add(egui::DragValue::new(&mut pool.full_mark).speed(0.5).range(0.0..=100.0));
+            ui.label("去掉最低分的项数");
+            ui.add(egui::DragValue::new(&mut pool.drop_lowest).range(0..=20));
+        });
+
+        let mut removed = None;
+        egui::Grid::new(title).show(ui, |ui| {
+            for header in ["名称", "满分", "权重", "可去掉"] {
+                ui.strong(header);
+            }
+            if deadlines {
+                ui.strong("截止时间");
+            }
+            ui.end_row();
+            for (index, assignment) in pool.assignments.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(&mut assignment.name).desired_width(80.0));
+                ui.add(egui::DragValue::new(&mut assignment.max_points).speed(0.5).range(0.1..=f32::MAX));
+                ui.add(egui::DragValue::new(&mut assignment.weight).speed(0.05).range(0.0..=f32::MAX));
+                ui.checkbox(&mut assignment.droppable, "");
+                if deadlines {
+                    deadline_edit(ui, egui::Id::new((title, "deadline", index)), &mut assignment.deadline);
+                }
+                remove_button(ui, index, &mut removed);
+                ui.end_row();
+            }
+        });
+        if let Some(index) = removed {
+            pool.assignments.remove(index);
+        }
+
+        if pool.is_configured() && pool.assignments.len() < count {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("总评表中有 {} 项{}，多出的分项没有分项信息，不计入成绩", count, prefix),
+            );
+        }
+        ui.horizontal(|ui| {
+            if ui.button("添加分项").clicked() {
+                pool.fill(pool.assignments.len() + 1, prefix);
+            }
+            if ui
+                .add_enabled(pool.assignments.len() < count, egui::Button::new("按总评表补全"))
+                .clicked()
+            {
+                pool.fill(count, prefix);
+            }
+        });
+    });
+}
+
+/// 规则列表中的删除按钮，点击时记录要删除的行
+fn remove_button(ui: &mut egui::Ui, index: usize, removed: &mut Option<usize>) {
+    if ui.small_button("删除").clicked() {
+        *removed = Some(index);
+    }
+}
+
+pub fn grade_scale_ui(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
+    egui::CollapsingHeader::new("五级制与绩点换算").show(ui, |ui| {
+        let mut state = state.lock().unwrap();
+        let before = state.grade_scale.clone();
+        let scale = &mut state.grade_scale;
+        ui.label("报送成绩不低于最低分时记为对应等级与绩点，已调分时按调整后总分换算");
+
+        let mut removed = None;
+        egui::Grid::new("grade_level_grid")
+            .num_columns(3)
+            .show(ui, |ui| {
+                ui.strong("最低分");
+                ui.strong("五级制");
+                ui.end_row();
+                for (index, level) in scale.levels.iter_mut().enumerate() {
+                    ui.add(egui::DragValue::new(&mut level.min_score).speed(0.5).range(0.0..=100.0));
+                    ui.add(egui::TextEdit::singleline(&mut level.name).desired_width(60.0));
+                    remove_button(ui, index, &mut removed);
+                    ui.end_row();
+                }
+            });
+        if let Some(index) = removed.take() {
+            scale.levels.remove(index);
+        }
+        if ui.button("添加等级").clicked() {
+            scale.levels.push(GradeLevel {
+                min_score: 0.0,
+                name: String::new(),
+            });
+        }
+
+        egui::Grid::new("grade_point_grid")
+            .num_columns(3)
+            .show(ui, |ui| {
+                ui.strong("最低分");
+                ui.strong("绩点");
+                ui.end_row();
+                for (index, point) in scale.points.iter_mut().enumerate() {
+                    ui.add(egui::DragValue::new(&mut point.min_score).speed(0.5).range(0.0..=100.0));
+                    ui.add(egui::DragValue::new(&mut point.point).speed(0.1).range(0.0..=5.0).max_decimals(2));
+                    remove_button(ui, index, &mut removed);
+                    ui.end_row();
+                }
+            });
+        if let Some(index) = removed {
+            scale.points.remove(index);
+        }
+        if ui.button("添加绩点").clicked() {
+            scale.points.push(GradePoint {
+                min_score: 0.0,
+                point: 0.0,
+            });
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("恢复浙大 5 分制").clicked() {
+                state.grade_scale = GradeScale::default();
+            }
+            if ui.button("按当前规则重新换算").clicked() {
+                state.grade_scale.sort();
+                state.edit("重新换算五级制与绩点", |state| state.update_reported_scores());
+                state.log(
+                    "五级制与绩点换算".to_string(),
+                    "已按当前规则重新换算五级制与绩点".to_string(),
+                );
+            }
+        });
+        if state.grade_scale != before {
+            state.mark_dirty();
+        }
+    });
+}
@@ -0,0 +1,155 @@
+use std::sync::{Arc, Mutex};
+
+use ads::app_state::*;
+use ads::stats::{table_summaries, Summary, PASS_RATIO};
+
+/// 直方图的大小
+const HISTOGRAM_SIZE: egui::Vec2 = egui::vec2(420.0, 160.0);
+
+/// 成绩分布统计窗口，统计结果只在切换表、数据或计算规则变化时重新计算
+#[derive(Default)]
+pub struct StatisticsPanel {
+    pub open: bool,
+    /// 生成统计结果时的表与数据版本
+    key: Option<(u8, u64)>,
+    /// 生成统计结果时的计算规则，满分随规则变化
+    rules: Option<(GradingPolicy, ProjectRules)>,
+    summaries: Vec<Summary>,
+    /// 显示直方图的成绩项
+    selected: usize,
+}
+
+impl StatisticsPanel {
+    fn update(&mut self, state: &AppState) {
+        let key = (state.table_type.clone() as u8, state.revision);
+        let rules_unchanged = self
+            .rules
+            .as_ref()
+            .is_some_and(|(policy, rules)| *policy == state.grading_policy && *rules == state.project_rules);
+        if self.key == Some(key) && rules_unchanged {
+            return;
+        }
+        if self.key.is_some_and(|(table, _)| table != key.0) {
+            self.selected = 0;
+        }
+        self.summaries = table_summaries(state, &state.table_type);
+        self.selected = self.selected.min(self.summaries.len().saturating_sub(1));
+        self.key = Some(key);
+        if !rules_unchanged {
+            self.rules = Some((state.grading_policy.clone(), state.project_rules.clone()));
+        }
+    }
+}
+
+fn percent(rate: Option<f32>) -> String {
+    rate.map_or("-".to_string(), |rate| format!("{:.1}%", rate * 100.0))
+}
+
+/// 用 painter 绘制直方图，鼠标悬停时显示该段的范围与人数
+fn histogram(ui: &mut egui::Ui, summary: &Summary) {
+    let (response, painter) = ui.allocate_painter(HISTOGRAM_SIZE, egui::Sense::hover());
+    let rect = response.rect;
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+
+    let font = egui::FontId::proportional(11.0);
+    let label_height = 14.0;
+    let plot = egui::Rect::from_min_max(
+        rect.min + egui::vec2(4.0, label_height),
+        rect.max - egui::vec2(4.0, label_height),
+    );
+    let bins = summary.histogram.len();
+    let highest = summary.histogram.iter().copied().max().unwrap_or(0).max(1);
+    let bar_width = plot.width() / bins as f32;
+    let (low, high) = summary.range;
+    let step = (high - low) / bins as f32;
+    let hovered = response.hover_pos().map(|pos| ((pos.x - plot.left()) / bar_width).floor() as usize);
+
+    for (bin, &count) in summary.histogram.iter().enumerate() {
+        let left = plot.left() + bar_width * bin as f32;
+        let height = plot.height() * count as f32 / highest as f32;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left + 1.0, plot.bottom() - height),
+            egui::pos2(left + bar_width - 1.0, plot.bottom()),
+        );
+        let color = if hovered == Some(bin) {
+            visuals.selection.bg_fill
+        } else {
+            visuals.widgets.inactive.bg_fill
+        };
+        painter.rect_filled(bar, 0.0, color);
+        if count > 0 {
+            painter.text(
+                egui::pos2(bar.center().x, bar.top() - 1.0),
+                egui::Align2::CENTER_BOTTOM,
+                count.to_string(),
+                font.clone(),
+                visuals.text_color(),
+            );
+        }
+    }
+    for (x, value, align) in [
+        (plot.left(), low, egui::Align2::LEFT_TOP),
+        (plot.center().x, (low + high) / 2.0, egui::Align2::CENTER_TOP),
+        (plot.right(), high, egui::Align2::RIGHT_TOP),
+    ] {
+        let position = egui::pos2(x, plot.bottom() + 2.0);
+        painter.text(position, align, format!("{:.1}", value), font.clone(), visuals.weak_text_color());
+    }
+
+    if let Some(bin) = hovered.filter(|bin| *bin < bins) {
+        let start = low + step * bin as f32;
+        let closing = if bin + 1 == bins { "]" } else { ")" };
+        response.on_hover_text(format!(
+            "[{:.1}, {:.1}{}: {} 人",
+            start,
+            start + step,
+            closing,
+            summary.histogram[bin]
+        ));
+    }
+}
+
+/// 当前表每一项成绩的统计表与所选成绩的直方图
+pub fn statistics_window(state: Arc<Mutex<AppState>>, panel: &mut StatisticsPanel, ctx: &egui::Context) {
+    if !panel.open {
+        return;
+    }
+    panel.update(&state.lock().unwrap());
+    let mut open = panel.open;
+    egui::Window::new("成绩分布").open(&mut open).show(ctx, |ui| {
+        if panel.summaries.is_empty() {
+            ui.label("当前表没有可统计的成绩");
+            return;
+        }
+        egui::Grid::new("statistics_grid").striped(true).show(ui, |ui| {
+            for title in ["成绩", "人数", "平均分", "中位数", "标准差", "最低", "下四分位", "上四分位", "最高", "及格率"] {
+                ui.strong(title);
+            }
+            ui.end_row();
+            for (index, summary) in panel.summaries.iter().enumerate() {
+                ui.selectable_value(&mut panel.selected, index, summary.title)
+                    .on_hover_text("显示该项的直方图");
+                ui.label(summary.count.to_string());
+                for value in [
+                    summary.mean,
+                    summary.median,
+                    summary.std_dev,
+                    summary.min,
+                    summary.q1,
+                    summary.q3,
+                    summary.max,
+                ] {
+                    ui.label(format!("{:.2}", value));
+                }
+                ui.label(percent(summary.pass_rate));
+                ui.end_row();
+            }
+        });
+        ui.separator();
+        let summary = &panel.summaries[panel.selected];
+        ui.label(format!("{} 分布(及格线为满分的 {:.0}%)", summary.title, PASS_RATIO * 100.0));
+        histogram(ui, summary);
+    });
+    panel.open = open;
+}
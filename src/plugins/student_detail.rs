@@ -0,0 +1,166 @@
+use std::sync::{Arc, Mutex};
+
+use ads::app_state::*;
+use ads::assignment::AssignmentPool;
+use ads::deadline::format_time;
+use ads::score::Score;
+
+/// 学生详情窗口，显示一名学生的各项成绩与迟交扣分
+#[derive(Default)]
+pub struct StudentDetail {
+    pub open: bool,
+    /// 所选学生的学号
+    student_id: String,
+}
+
+fn score_text(score: &Score) -> String {
+    match score {
+        Score::Scored(value) => format!("{:.2}", value),
+        status => status.label().to_string(),
+    }
+}
+
+/// 分项名称，没有分项信息时为 `prefix` 加序号
+fn item_name(pool: &AssignmentPool, prefix: &str, index: usize) -> String {
+    pool.assignments
+        .get(index)
+        .map_or_else(|| format!("{}{}", prefix, index + 1), |assignment| assignment.name.clone())
+}
+
+/// 总评各项
+fn summary_grid(ui: &mut egui::Ui, row: &FinalItem) {
+    egui::Grid::new("student_detail_summary").striped(true).show(ui, |ui| {
+        let mut line = |title: &str, text: String| {
+            ui.label(title);
+            ui.label(text);
+            ui.end_row();
+        };
+        line("总分", format!("{:.2}", row.final_score));
+        if let Some(adjusted) = row.adjusted_score {
+            line("调整后总分", format!("{:.2}", adjusted));
+        }
+        line("五级制", row.grade_level.clone());
+        line("绩点", format!("{:.1}", row.grade_point));
+        line("平时分", format!("{:.2}", row.general_score));
+        line("期末", score_text(&row.final_exam_score));
+        line("期中", score_text(&row.midterm_score));
+        line("作业", format!("{:.2}", row.homework_score_sum));
+        line("讨论", format!("{:.2}", row.discussion_score_sum));
+        line("Project", format!("{:.2}", row.project_score_sum));
+        line("Bonus", format!("{:.2}", row.bonus_score));
+    });
+}
+
+/// 作业分项：得分、提交时间、截止时间、迟交扣分与计入的分数
+fn homework_grid(ui: &mut egui::Ui, row: &FinalItem, policy: &GradingPolicy) {
+    let deductions = row.late_deductions(policy);
+    let counted = row.late_homework_score(policy);
+    let dropped = if policy.homework.is_configured() {
+        policy.homework.dropped(&counted, policy.missing_as_zero)
+    } else {
+        vec![]
+    };
+    egui::Grid::new("student_detail_homework").striped(true).show(ui, |ui| {
+        for title in ["作业", "得分", "提交时间", "截止时间", "迟交", "扣分", "计入"] {
+            ui.strong(title);
+        }
+        ui.end_row();
+        for (index, score) in row.homework_score.iter().enumerate() {
+            let submitted = row.homework_submitted.get(index).copied().flatten();
+            let deadline = policy.homework.assignments.get(index).and_then(|assignment| assignment.deadline);
+            let deduction = deductions.iter().find(|deduction| deduction.index == index);
+            ui.label(item_name(&policy.homework, "作业", index));
+            ui.label(score_text(score));
+            ui.label(submitted.as_ref().map_or("-".to_string(), format_time));
+            ui.label(deadline.as_ref().map_or("-".to_string(), format_time));
+            match deduction {
+                Some(deduction) => {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("{} 天", deduction.days));
+                    ui.label(format!("-{:.2} ({}%)", deduction.points, deduction.percent));
+                }
+                None => {
+                    ui.label("-");
+                    ui.label("-");
+                }
+            }
+            if dropped.contains(&index) {
+                ui.weak("已去掉");
+            } else {
+                ui.label(score_text(&counted[index]));
+            }
+            ui.end_row();
+        }
+    });
+    if !deductions.is_empty() {
+        let total: f32 = deductions.iter().map(|deduction| deduction.points).sum();
+        ui.label(format!("迟交 {} 次，共扣 {:.2} 分(按分项原始分计)", deductions.len(), total));
+    }
+}
+
+/// 讨论分项：得分与是否被去掉
+fn discussion_grid(ui: &mut egui::Ui, row: &FinalItem, policy: &GradingPolicy) {
+    let dropped = if policy.discussion.is_configured() {
+        policy.discussion.dropped(&row.discussion_score, policy.missing_as_zero)
+    } else {
+        vec![]
+    };
+    egui::Grid::new("student_detail_discussion").striped(true).show(ui, |ui| {
+        for title in ["讨论", "得分", ""] {
+            ui.strong(title);
+        }
+        ui.end_row();
+        for (index, score) in row.discussion_score.iter().enumerate() {
+            ui.label(item_name(&policy.discussion, "讨论", index));
+            ui.label(score_text(score));
+            if dropped.contains(&index) {
+                ui.weak("已去掉");
+            } else {
+                ui.label("");
+            }
+            ui.end_row();
+        }
+    });
+}
+
+pub fn student_detail_window(state: Arc<Mutex<AppState>>, detail: &mut StudentDetail, ctx: &egui::Context) {
+    if !detail.open {
+        return;
+    }
+    let state = state.lock().unwrap();
+    let mut open = detail.open;
+    egui::Window::new("学生详情").open(&mut open).show(ctx, |ui| {
+        let selected = state
+            .final_table
+            .iter()
+            .find(|row| row.student_info.student_id == detail.student_id);
+        let label = selected.map_or("选择学生".to_string(), |row| {
+            format!("{} {}", row.student_info.student_id, row.student_info.student_name)
+        });
+        egui::ComboBox::from_id_source("student_detail_select")
+            .selected_text(label)
+            .width(200.0)
+            .show_ui(ui, |ui| {
+                for row in &state.final_table {
+                    let info = &row.student_info;
+                    ui.selectable_value(
+                        &mut detail.student_id,
+                        info.student_id.clone(),
+                        format!("{} {}", info.student_id, info.student_name),
+                    );
+                }
+            });
+        let Some(row) = selected else {
+            ui.label("总评成绩表中没有所选的学生");
+            return;
+        };
+        let policy = &state.grading_policy;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            summary_grid(ui, row);
+            ui.separator();
+            homework_grid(ui, row, policy);
+            ui.separator();
+            discussion_grid(ui, row, policy);
+        });
+    });
+    detail.open = open;
+}
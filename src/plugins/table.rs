@@ -1,270 +1,322 @@
-use super::app_state::AppState;
-use super::app_state::TableType;
+use ads::app_state::AppState;
+use ads::app_state::TableType;
+use ads::columns::{Cell, CellMut, TableColumn, TableModel};
+use ads::history::Snapshot;
+use ads::score::Score;
+use ads::table_view::{is_valid_filter, TableView};
 use egui_extras::{Column, TableBuilder};
 use std::sync::{Arc, Mutex};
 
-pub fn table_ui(state: Arc<Mutex<AppState>>, ui: &mut egui::Ui) {
-    let available_height = ui.available_height();
-    let table_type = {
-        let state = state.lock().unwrap();
-        state.table_type.clone()
+/// 本帧中是否有单元格仍在编辑(获得焦点或正在拖动)
+#[derive(Default)]
+struct CellEdits {
+    active: bool,
+}
+
+impl CellEdits {
+    fn track(&mut self, response: &egui::Response) -> bool {
+        self.active |= response.has_focus() || response.dragged();
+        response.changed()
+    }
+
+    /// 可编辑的分数
+    fn number(&mut self, ui: &mut egui::Ui, value: &mut f32) -> bool {
+        let response = ui.add(
+            egui::DragValue::new(value)
+                .speed(0.1)
+                .max_decimals(2)
+                .range(0.0..=f32::MAX),
+        );
+        self.track(&response)
+    }
+
+    /// 可编辑的带状态分数：有分数时拖动修改，右键菜单设置未提交、豁免、缺考
+    fn score(&mut self, ui: &mut egui::Ui, value: &mut Score) -> bool {
+        let mut changed = false;
+        let response = match &mut *value {
+            Score::Scored(number) => {
+                let response = ui.add(
+                    egui::DragValue::new(number)
+                        .speed(0.1)
+                        .max_decimals(2)
+                        .range(0.0..=f32::MAX),
+                );
+                changed |= self.track(&response);
+                response
+            }
+            status => ui.add(
+                egui::Label::new(egui::RichText::new(status.label()).italics().weak()).sense(egui::Sense::click()),
+            ),
+        };
+        response.on_hover_text("右键设置状态").context_menu(|ui| {
+            for status in Score::STATUSES {
+                if ui.add_enabled(*value != status, egui::Button::new(status.label())).clicked() {
+                    *value = status;
+                    changed = true;
+                    ui.close_menu();
+                }
+            }
+            if ui.add_enabled(!value.is_scored(), egui::Button::new("改为分数")).clicked() {
+                *value = Score::default();
+                changed = true;
+                ui.close_menu();
+            }
+        });
+        changed
+    }
+
+    /// 可编辑的编号，如组号、展示报告 ID
+    fn index(&mut self, ui: &mut egui::Ui, value: &mut usize) -> bool {
+        let response = ui.add(egui::DragValue::new(value).speed(0.1));
+        self.track(&response)
+    }
+
+    /// 可编辑的文本，如学号、姓名
+    fn text(&mut self, ui: &mut egui::Ui, value: &mut String) -> bool {
+        let response = ui.add(egui::TextEdit::singleline(value).desired_width(80.0));
+        self.track(&response)
+    }
+
+    /// 按列模型显示一个单元格，可编辑的列显示输入框，只读的列显示缓存的 `text`，返回是否修改
+    fn cell<T: TableModel>(&mut self, ui: &mut egui::Ui, item: &mut T, key: T::Key, text: &str) -> bool {
+        match item.cell_mut(key) {
+            CellMut::Text(value) => self.text(ui, value),
+            CellMut::Number(value) => self.number(ui, value),
+            CellMut::Score(value) => self.score(ui, value),
+            CellMut::Index(value) => self.index(ui, value),
+            CellMut::ReadOnly => {
+                if !text.is_empty() {
+                    ui.label(text).on_hover_text("由其他列计算，修改其他列后自动更新");
+                }
+                false
+            }
+        }
+    }
+}
+
+fn cell_text(cell: Cell) -> String {
+    match cell {
+        Cell::Text(text) => text.to_string(),
+        Cell::Number(value) | Cell::Score(Score::Scored(value)) => format!("{:.2}", value),
+        Cell::Score(status) => status.label().to_string(),
+        Cell::Index(value) => value.to_string(),
+        Cell::Empty => String::new(),
+    }
+}
+
+/// 表格的显示缓存：筛选排序后的行顺序与单元格文本，只在数据或视图变化时重新生成
+#[derive(Default)]
+pub struct TableCache {
+    /// 生成缓存时的表、数据版本与视图
+    key: Option<(u8, u64, TableView)>,
+    /// 需要显示的行的下标
+    visible: Vec<usize>,
+    /// 单元格文本，按行号、列号索引
+    texts: Vec<Vec<String>>,
+    /// 上一帧中被编辑的行
+    edited: Option<usize>,
+}
+
+impl TableCache {
+    /// `keep_order` 为真时只更新文本，保持行的顺序；若数据只因编辑一行而变化，只更新该行的文本
+    fn update<T: TableModel>(
+        &mut self,
+        key: (u8, u64, TableView),
+        rows: &[T],
+        columns: &[TableColumn<T::Key>],
+        keep_order: bool,
+    ) {
+        let edited = self.edited.take();
+        if self.key.as_ref() == Some(&key) {
+            return;
+        }
+        let same_table = self.key.as_ref().is_some_and(|(table, ..)| *table == key.0);
+        if keep_order && same_table && self.visible.iter().all(|&index| index < rows.len()) {
+            let only_edited = self
+                .key
+                .as_ref()
+                .is_some_and(|(_, revision, view)| revision + 1 == key.1 && *view == key.2);
+            if let Some(index) = edited.filter(|&index| only_edited && index < self.texts.len()) {
+                if self.texts.len() == rows.len() && self.texts[index].len() == columns.len() {
+                    self.texts[index] = row_texts(&rows[index], columns);
+                    self.key = Some(key);
+                    return;
+                }
+            }
+        } else {
+            self.visible = key.2.visible_rows(rows, columns);
+        }
+        self.texts = rows.iter().map(|row| row_texts(row, columns)).collect();
+        self.key = Some(key);
+    }
+}
+
+fn row_texts<T: TableModel>(row: &T, columns: &[TableColumn<T::Key>]) -> Vec<String> {
+    columns.iter().map(|column| cell_text(row.cell(column.key))).collect()
+}
+
+/// 编辑结束后检查学号/组号，有误则撤回这次编辑，否则写入历史记录
+fn finish_edit(state: &mut AppState, table_type: &TableType) {
+    let Some(before) = state.table_edit.take() else {
+        return;
     };
-    match table_type {
-        TableType::FinalScore => {
-            let table = TableBuilder::new(ui)
-                .resizable(true)
-                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .min_scrolled_height(0.0)
-                .max_scroll_height(available_height);
-            table
-                .header(20.0, |mut header| {
-                    header.col(|ui| {
-                        ui.strong("学号");
-                    });
-                    header.col(|ui| {
-                        ui.strong("姓名");
-                    });
-                    header.col(|ui| {
-                        ui.strong("总分");
-                    });
-                    header.col(|ui| {
-                        ui.strong("平时分");
-                    });
-                    header.col(|ui| {
-                        ui.strong("期末(40%)");
-                    });
-                    header.col(|ui| {
-                        ui.strong("期中(10%)");
-                    });
-                    header.col(|ui| {
-                        ui.strong("作业(10%)");
-                    });
-                    header.col(|ui| {
-                        ui.strong("讨论(10%)");
-                    });
-                    header.col(|ui| {
-                        ui.strong("Bonus");
-                    });
-                    header.col(|ui| {
-                        ui.strong("讨论分项");
-                    });
-                    header.col(|ui| {
-                        ui.strong("作业分项");
-                    });
-                })
-                .body(|mut body| {
-                    let state = state.lock().unwrap();
-                    for student in &state.final_table {
-                        body.row(20.0, |mut row| {
-                            row.col(|ui| {
-                                ui.label(student.student_info.student_id.clone());
-                            });
-                            row.col(|ui| {
-                                ui.label(student.student_info.student_name.clone());
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.final_score));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.general_score));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.final_exam_score));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.midterm_score));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.homework_score_sum));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.discussion_score_sum));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.bonus_score));
-                            });
-                            row.col(|ui| {
-                                ui.horizontal(|ui| {
-                                    for score in &student.discussion_score {
-                                        ui.label(format!("{:.2}", score));
-                                    }
-                                });
-                            });
-                            row.col(|ui| {
-                                ui.horizontal(|ui| {
-                                    for score in &student.homework_score {
-                                        ui.label(format!("{:.2}", score));
-                                    }
-                                });
-                            });
-                        });
-                    }
-                });
+    if let Err(e) = state.check_keys(table_type) {
+        before.restore(state);
+        state.revision += 1;
+        state.log("编辑表格".to_string(), format!("{}，已撤回修改", e));
+    } else if before != Snapshot::take(state) {
+        state.history.push("编辑表格", before);
+        state.mark_dirty();
+    }
+}
+
+/// 搜索框与筛选状态
+fn view_toolbar(ui: &mut egui::Ui, view: &mut TableView, shown: usize, total: usize) {
+    ui.horizontal(|ui| {
+        ui.label("搜索");
+        ui.add(
+            egui::TextEdit::singleline(&mut view.search)
+                .hint_text("学号 / 姓名 / 拼音首字母")
+                .desired_width(160.0),
+        );
+        if ui
+            .add_enabled(view.is_filtered() || view.sort.is_some(), egui::Button::new("清除筛选与排序"))
+            .clicked()
+        {
+            view.clear();
         }
-        TableType::ProjectScore => {
-            let table = TableBuilder::new(ui)
-                .resizable(true)
-                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .min_scrolled_height(0.0)
-                .max_scroll_height(available_height);
-            table.header(20.0, |mut header| {
-                header.col(|ui| {
-                    ui.strong("组号");
-                });
-                header.col(|ui| {
-                    ui.strong("Project 总分(30)");
-                });
-                header.col(|ui| {
-                    ui.strong("展示分数(6)");
-                });
-                header.col(|ui| {
-                    ui.strong("Bonus 分数");
-                });
-                header.col(|ui| {
-                    ui.strong("Report 总分(20)");
-                });
-                header.col(|ui| {
-                    ui.strong("PR 总分(4)");
-                });
-                header.col(|ui| {
-                    ui.strong("展示报告 ID");
-                });
-                header.col(|ui| {
-                    ui.strong("Report(20)");
-                });
-                header.col(|ui| {
-                    ui.strong("PR(40)");
-                });
-            }).body(|mut body| {
-                let state = state.lock().unwrap();
-                    for student in &state.project_table {
-                        body.row(20.0, |mut row| {
-                            row.col(|ui| {
-                                ui.label(format!("{}", student.group_id.clone()));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.total_score));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.presentation_score));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.bonus_score));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.report_score_sum));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:.2}", student.pr_score_sum));
-                            });
-                            row.col(|ui| {
-                                ui.label(format!("{:}", student.presentation_id));
-                            });
-                            row.col(|ui| {
-                                ui.horizontal(|ui| {
-                                    for score in &student.report_score {
-                                        ui.label(format!("{:.2}", score));
-                                    }
-                                });
-                            });
-                            row.col(|ui| {
-                                ui.horizontal(|ui| {
-                                    for score in &student.pr_score {
-                                        ui.label(format!("{:.2}", score));
-                                    }
-                                });
-                            });
-                        });
-                    }
-            });
+        ui.label(format!("显示 {} / {} 行", shown, total));
+    });
+}
+
+/// 表头：点击标题切换排序，下方输入筛选条件
+fn header_cell(ui: &mut egui::Ui, title: &str, column: &str, view: &mut TableView) {
+    ui.vertical(|ui| {
+        let arrow = match view.sort_of(column) {
+            Some(false) => " ⬆",
+            Some(true) => " ⬇",
+            None => "",
+        };
+        let label = egui::Label::new(egui::RichText::new(format!("{}{}", title, arrow)).strong())
+            .sense(egui::Sense::click());
+        if ui.add(label).on_hover_text("点击排序").clicked() {
+            view.toggle_sort(column);
         }
-        TableType::GroupList => {
-            let table = TableBuilder::new(ui)
-                .resizable(true)
-                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .column(Column::auto())
-                .min_scrolled_height(0.0)
-                .max_scroll_height(available_height);
-            table
-                .header(20.0, |mut header| {
-                    header.col(|ui| {
-                        ui.strong("组号");
-                    });
-                    header.col(|ui| {
-                        ui.strong("组长学号");
-                    });
-                    header.col(|ui| {
-                        ui.strong("组长姓名");
-                    });
-                    header.col(|ui| {
-                        ui.strong("组员学号");
-                    });
-                    header.col(|ui| {
-                        ui.strong("组员姓名");
-                    });
-                    header.col(|ui| {
-                        ui.strong("组员学号");
-                    });
-                    header.col(|ui| {
-                        ui.strong("组员姓名");
+        let filter = view.filter_mut(column);
+        let valid = is_valid_filter(filter);
+        let mut edit = egui::TextEdit::singleline(filter)
+            .hint_text("筛选")
+            .desired_width(60.0);
+        if !valid {
+            edit = edit.text_color(ui.visuals().error_fg_color);
+        }
+        ui.add(edit)
+            .on_hover_text("如 < 60、>= 90、= 12，或输入文本按包含筛选");
+    });
+}
+
+/// 按列模型显示一张表。`table` 取得数据表，`recalculate` 在编辑一行后重新计算该行的派生列。
+/// 只绘制可见范围内的行，持有锁的时间也只覆盖这些行
+fn show_table<T: TableModel + Clone>(
+    state: Arc<Mutex<AppState>>,
+    cache: &mut TableCache,
+    ui: &mut egui::Ui,
+    table_type: &TableType,
+    table: fn(&mut AppState) -> &mut Vec<T>,
+    recalculate: fn(&mut T, &AppState),
+) {
+    let (columns, mut view, total) = {
+        let mut state = state.lock().unwrap();
+        let view = state.table_views.get_mut(table_type).clone();
+        let (policy, rules) = (state.grading_policy.clone(), state.project_rules.clone());
+        // 编辑过程中保持行的顺序，避免正在编辑的行因排序或筛选移动、消失
+        let editing = state.table_edit.is_some();
+        let key = (table_type.clone() as u8, state.revision, view.clone());
+        let rows = table(&mut state);
+        let columns = T::columns(rows, &policy, &rules);
+        cache.update(key, rows, &columns, editing);
+        (columns, view, rows.len())
+    };
+    view_toolbar(ui, &mut view, cache.visible.len(), total);
+
+    let available_height = ui.available_height();
+    let mut edits = CellEdits::default();
+    let mut edited = None;
+    let mut builder = TableBuilder::new(ui)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center));
+    for _ in &columns {
+        builder = builder.column(Column::auto());
+    }
+    builder
+        .min_scrolled_height(0.0)
+        .max_scroll_height(available_height)
+        .header(44.0, |mut header| {
+            for column in &columns {
+                header.col(|ui| header_cell(ui, &column.title, &column.id, &mut view));
+            }
+        })
+        .body(|body| {
+            let mut state = state.lock().unwrap();
+            body.rows(20.0, cache.visible.len(), |mut row| {
+                let index = cache.visible[row.index()];
+                let mut item = table(&mut state)[index].clone();
+                let mut changed = false;
+                for (column, text) in columns.iter().zip(&cache.texts[index]) {
+                    row.col(|ui| {
+                        changed |= edits.cell(ui, &mut item, column.key, text);
                     });
-                })
-                .body(|mut body| {
-                    let state = state.lock().unwrap();
-                    for student in &state.group_table {
-                        body.row(20.0, |mut row| {
-                            row.col(|ui| {
-                                ui.label(student.group_id.to_string());
-                            });
-                            row.col(|ui| {
-                                ui.label(student.group_members[0].student_id.clone());
-                            });
-                            row.col(|ui| {
-                                ui.label(student.group_members[0].student_name.clone());
-                            });
-                            row.col(|ui| {
-                                ui.label(student.group_members[1].student_id.clone());
-                            });
-                            row.col(|ui| {
-                                ui.label(student.group_members[1].student_name.clone());
-                            });
-                            if student.group_members.len() > 2 {
-                                row.col(|ui| {
-                                    ui.label(student.group_members[2].student_id.clone());
-                                });
-                                row.col(|ui| {
-                                    ui.label(student.group_members[2].student_name.clone());
-                                });
-                            }
-                        });
+                }
+                if changed {
+                    recalculate(&mut item, &state);
+                    if state.table_edit.is_none() {
+                        state.table_edit = Some(Snapshot::take(&state));
                     }
-                });
-        }
+                    table(&mut state)[index] = item;
+                    state.revision += 1;
+                    // 编辑尚未结束时关闭窗口也要保存已输入的值
+                    state.mark_dirty();
+                    edited = Some(index);
+                }
+            });
+        });
+    cache.edited = edited;
+
+    let mut state = state.lock().unwrap();
+    // 一次编辑(从开始输入或拖动到结束)记为一条可撤销记录
+    if !edits.active {
+        finish_edit(&mut state, table_type);
+    }
+    view.remove_empty_filters();
+    let saved_view = state.table_views.get_mut(table_type);
+    if *saved_view != view {
+        *saved_view = view;
+        state.mark_dirty();
+    }
+}
+
+pub fn table_ui(state: Arc<Mutex<AppState>>, cache: &mut TableCache, ui: &mut egui::Ui) {
+    let table_type = state.lock().unwrap().table_type.clone();
+    match table_type {
+        TableType::FinalScore => show_table(
+            state,
+            cache,
+            ui,
+            &table_type,
+            |state| &mut state.final_table,
+            |row, state| {
+                row.calaculate(&state.grading_policy);
+                row.update_reported(&state.grading_policy, state.curve.applied, &state.grade_scale);
+            },
+        ),
+        TableType::ProjectScore => show_table(
+            state,
+            cache,
+            ui,
+            &table_type,
+            |state| &mut state.project_table,
+            |row, state| row.calaculate(&state.project_rules),
+        ),
+        TableType::GroupList => show_table(state, cache, ui, &table_type, |state| &mut state.group_table, |_, _| {}),
     }
 }
@@ -0,0 +1,81 @@
+use std::sync::{Arc, Mutex};
+
+use ads::app_state::*;
+use ads::table_io::{export_table_text, preview_import};
+
+fn table_label(table_type: &TableType) -> &'static str {
+    match table_type {
+        TableType::FinalScore => "总评成绩",
+        TableType::ProjectScore => "Project 成绩",
+        TableType::GroupList => "组队名单",
+    }
+}
+
+/// "导入"：解析输入区并弹出预览；"导出"：将当前表写入输入区，不修改表
+pub fn table_io_ui(state: &mut AppState, ui: &mut egui::Ui) {
+    if ui.button("导入").on_hover_text("将输入区的数据导入当前表，确认前可预览").clicked() {
+        let preview = preview_import(state, state.table_type.clone(), &state.input_text);
+        state.input_errors = preview.failed.clone();
+        state.import_preview = Some(preview);
+    }
+    if ui.button("导出").on_hover_text("将当前表写入输入区").clicked() {
+        let text = export_table_text(state, &state.table_type);
+        let label = format!("导出{}到输入区", table_label(&state.table_type));
+        state.edit(&label, |state| state.input_text = text);
+    }
+}
+
+fn key_list(ui: &mut egui::Ui, title: &str, key_label: &str, keys: &[String]) {
+    if keys.is_empty() {
+        return;
+    }
+    egui::CollapsingHeader::new(format!("{} ({})", title, keys.len()))
+        .id_source(title)
+        .show(ui, |ui| {
+            ui.label(format!("{}: {}", key_label, keys.join(", ")));
+        });
+}
+
+/// 导入预览窗口，确认后才替换数据表
+pub fn import_preview_window(state: Arc<Mutex<AppState>>, ctx: &egui::Context) {
+    let mut state = state.lock().unwrap();
+    let Some(preview) = &state.import_preview else {
+        return;
+    };
+    let mut confirmed = false;
+    let mut cancelled = false;
+    egui::Window::new(format!("导入预览 - {}", table_label(&preview.table_type)))
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.label(preview.summary());
+            key_list(ui, "新增", preview.key_label, &preview.added);
+            key_list(ui, "修改", preview.key_label, &preview.changed);
+            key_list(ui, "删除", preview.key_label, &preview.removed);
+            if !preview.failed.is_empty() {
+                egui::CollapsingHeader::new(format!("解析失败 ({})", preview.failed.len()))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                            for error in &preview.failed {
+                                ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+                            }
+                        });
+                    });
+                ui.label("解析失败的行不会导入。");
+            }
+            ui.horizontal(|ui| {
+                confirmed = ui.button("确认导入").clicked();
+                cancelled = ui.button("取消").clicked();
+            });
+        });
+    if confirmed {
+        if let Some(preview) = state.import_preview.take() {
+            let info = format!("导入{}", table_label(&preview.table_type));
+            let summary = preview.summary();
+            state.edit(&info, |state| state.apply_import(preview));
+            state.log(info, summary);
+        }
+    } else if cancelled {
+        state.import_preview = None;
+    }
+}
@@ -0,0 +1,46 @@
+use rand::seq::SliceRandom;
+
+/// 从以逗号、制表符或换行分隔的名单中随机抽取 `k` 个
+pub fn pick_random(input_text: &str, k: usize) -> Vec<&str> {
+    let mut numbers: Vec<&str> = input_text
+        .split(['\n', ',', '\t'])
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let mut rng = rand::thread_rng();
+    numbers.shuffle(&mut rng);
+
+    numbers.into_iter().take(k).collect()
+}
+
+/// 将名单随机分为大小为 `group_size` 的组，组号从 `first_group_id` 开始。
+///
+/// 返回组队名单格式的文本，每行为 `组号,学号,姓名...`。
+pub fn group_randomly(input_text: &str, group_size: usize, first_group_id: usize) -> String {
+    let mut numbers: Vec<&str> = input_text
+        .split(['\n', ',', '\t'])
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let mut rng = rand::thread_rng();
+    numbers.shuffle(&mut rng);
+
+    let selected_numbers: Vec<Vec<&str>> = numbers
+        .chunks(group_size.max(1))
+        .map(|chunk| chunk.to_vec())
+        .collect();
+
+    let mut output_text = String::new();
+    for (group_num, group) in (first_group_id..).zip(selected_numbers) {
+        output_text.push_str(format!("{}", group_num).as_str());
+        for member in group {
+            for item in member.split(' ') {
+                output_text.push_str(format!(",{}", item).as_str())
+            }
+        }
+        output_text.push('\n');
+    }
+    output_text
+}
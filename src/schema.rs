@@ -0,0 +1,205 @@
+//! app_state.json 的版本号与迁移。
+//!
+//! 文件顶层的 `schema_version` 记录写入时的格式版本，没有该字段的文件视为版本 0。
+//! 读取时按 `MIGRATIONS` 依次升级到 `SCHEMA_VERSION`，再反序列化为 `AppState`。
+
+use serde_json::{Map, Value};
+
+use crate::app_state::AppState;
+
+/// 当前的文件格式版本
+pub const SCHEMA_VERSION: u64 = 3;
+const VERSION_KEY: &str = "schema_version";
+
+/// `MIGRATIONS[n]` 将版本 n 的数据升级到版本 n + 1
+const MIGRATIONS: [fn(&mut Map<String, Value>); SCHEMA_VERSION as usize] =
+    [migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// 版本 0 没有版本号；计算结果为 NaN 的分数被写成了 null，读取时会失败，这里改为 0。
+/// 分项成绩中的 null 与版本 2 中 null 的含义一致，记为未提交
+fn migrate_v0_to_v1(state: &mut Map<String, Value>) {
+    for table in ["final_table", "project_table"] {
+        let Some(Value::Array(rows)) = state.get_mut(table) else {
+            continue;
+        };
+        for row in rows.iter_mut().filter_map(Value::as_object_mut) {
+            for value in row.values_mut() {
+                match value {
+                    Value::Null => *value = Value::from(0.0),
+                    Value::Array(items) => items
+                        .iter_mut()
+                        .filter(|item| item.is_null())
+                        .for_each(|item| *item = Value::from("missing")),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// 由成绩计算得到的 f32 字段，版本 1 中计算结果为 NaN 时写成了 null
+const COMPUTED_FIELDS: [&str; 11] = [
+    "final_score",
+    "general_score",
+    "project_score_sum",
+    "homework_score_sum",
+    "discussion_score_sum",
+    "bonus_score",
+    "grade_point",
+    "total_score",
+    "presentation_score",
+    "report_score_sum",
+    "pr_score_sum",
+];
+/// 版本 2 中改为 `Score` 的考试成绩与分项成绩字段
+const SCORE_FIELDS: [&str; 6] =
+    ["final_exam_score", "midterm_score", "discussion_score", "homework_score", "report_score", "pr_score"];
+
+/// 版本 1 中的分数: null、负数或非有限值都表示没有有效成绩，记为未提交
+fn migrate_v1_score(score: &mut Value) {
+    let valid = match score {
+        Value::Number(number) => number.as_f64().is_some_and(|n| n.is_finite() && n >= 0.0),
+        Value::String(_) => true,
+        _ => false,
+    };
+    if !valid {
+        *score = Value::from("missing");
+    }
+}
+
+/// 版本 2 中分项成绩与考试成绩可以是 "missing"、"excused"、"absent" 等状态，不再接受负数与 null，
+/// 旧版本程序无法读取，因此升级版本号。版本 1 中计算结果的 null 改为 0，无效的分数改为未提交
+fn migrate_v1_to_v2(state: &mut Map<String, Value>) {
+    for table in ["final_table", "project_table"] {
+        let Some(Value::Array(rows)) = state.get_mut(table) else {
+            continue;
+        };
+        for row in rows.iter_mut().filter_map(Value::as_object_mut) {
+            for (key, value) in row.iter_mut() {
+                if COMPUTED_FIELDS.contains(&key.as_str()) && value.is_null() {
+                    *value = Value::from(0.0);
+                } else if SCORE_FIELDS.contains(&key.as_str()) {
+                    match value {
+                        Value::Array(items) => items.iter_mut().for_each(migrate_v1_score),
+                        score => migrate_v1_score(score),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 版本 3 中排序与筛选按列的标识保存。版本 2 按列的位置保存，而列的位置会随调分与分项数量变化，
+/// 无法可靠地对应到列，因此清除保存的排序与筛选
+fn migrate_v2_to_v3(state: &mut Map<String, Value>) {
+    state.remove("table_views");
+}
+
+/// 将文件内容解析为 `AppState`，必要时先执行迁移
+pub fn decode_state(data: &str) -> Result<AppState, String> {
+    let mut value: Value = serde_json::from_str(data).map_err(|e| format!("JSON 格式错误: {}", e))?;
+    let state = value.as_object_mut().ok_or("顶层不是 JSON 对象".to_string())?;
+    let version = match state.remove(VERSION_KEY) {
+        None => 0,
+        Some(version) => version.as_u64().ok_or(format!("无效的 {}: {}", VERSION_KEY, version))?,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "数据文件版本为 {}，高于本程序支持的版本 {}，请升级程序",
+            version, SCHEMA_VERSION
+        ));
+    }
+    for migrate in &MIGRATIONS[version as usize..] {
+        migrate(state);
+    }
+    let mut state: AppState =
+        serde_json::from_value(value).map_err(|e| format!("数据与版本 {} 的格式不符: {}", SCHEMA_VERSION, e))?;
+    // 五级制与绩点由总分换算得到，旧文件中没有这两项
+    state.update_reported_scores();
+    Ok(state)
+}
+
+/// 将 `AppState` 序列化为带版本号的文件内容
+pub fn encode_state(state: &AppState) -> serde_json::Result<String> {
+    let mut value = serde_json::to_value(state)?;
+    if let Some(state) = value.as_object_mut() {
+        state.insert(VERSION_KEY.to_string(), Value::from(SCHEMA_VERSION));
+    }
+    serde_json::to_string(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::Score;
+
+    /// 版本 0 的文件：没有版本号，NaN 写成了 null
+    const V0: &str = r#"{
+        "input_text": "", "selected_k": 0, "group_size": 0, "output_text": [], "table_type": "FinalScore",
+        "final_table": [{
+            "student_info": {"student_id": "1", "student_name": "张三"},
+            "final_score": null, "general_score": 50, "final_exam_score": 80, "project_score_sum": 25,
+            "midterm_score": 70, "homework_score_sum": 8, "discussion_score_sum": null, "bonus_score": 0,
+            "discussion_score": [1, null], "homework_score": [20, 30]
+        }],
+        "project_table": [], "group_table": []
+    }"#;
+
+    #[test]
+    fn migrates_v0_through_current_version_and_round_trips() {
+        let state = decode_state(V0).unwrap();
+        let row = &state.final_table[0];
+        assert_eq!(row.final_score, 0.0);
+        assert_eq!(row.discussion_score_sum, 0.0);
+        assert_eq!(row.discussion_score, vec![Score::Scored(1.0), Score::Missing]);
+        assert_eq!(row.final_exam_score, Score::Scored(80.0));
+        assert_eq!(row.grade_level, "不及格");
+
+        let encoded = encode_state(&state).unwrap();
+        let value: Value = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(value[VERSION_KEY], Value::from(SCHEMA_VERSION));
+        let decoded = decode_state(&encoded).unwrap();
+        assert!(decoded.final_table == state.final_table);
+    }
+
+    /// 版本 1 的文件：计算结果可能是 null，分数可能是负数
+    const V1: &str = r#"{
+        "schema_version": 1,
+        "input_text": "", "selected_k": 0, "group_size": 0, "output_text": [], "table_type": "ProjectScore",
+        "final_table": [{
+            "student_info": {"student_id": "1", "student_name": "张三"},
+            "final_score": 60, "general_score": null, "final_exam_score": -1, "project_score_sum": 25,
+            "midterm_score": 70, "homework_score_sum": 8, "discussion_score_sum": 2, "bonus_score": 0,
+            "discussion_score": [1, -1], "homework_score": [20, null]
+        }],
+        "project_table": [{
+            "group_id": 1, "total_score": null, "presentation_score": 5, "bonus_score": 0,
+            "report_score_sum": 30, "pr_score_sum": null, "presentation_id": 1,
+            "report_score": [15, -1, 15], "pr_score": [-2, 20, 20]
+        }],
+        "group_table": []
+    }"#;
+
+    #[test]
+    fn migrates_v1_nulls_and_negative_scores() {
+        let state = decode_state(V1).unwrap();
+        let row = &state.final_table[0];
+        assert_eq!(row.general_score, 0.0);
+        assert_eq!(row.final_exam_score, Score::Missing);
+        assert_eq!(row.midterm_score, Score::Scored(70.0));
+        assert_eq!(row.discussion_score, vec![Score::Scored(1.0), Score::Missing]);
+        assert_eq!(row.homework_score, vec![Score::Scored(20.0), Score::Missing]);
+
+        let project = &state.project_table[0];
+        assert_eq!(project.total_score, 0.0);
+        assert_eq!(project.pr_score_sum, 0.0);
+        assert_eq!(project.report_score, vec![Score::Scored(15.0), Score::Missing, Score::Scored(15.0)]);
+        assert_eq!(project.pr_score, vec![Score::Missing, Score::Scored(20.0), Score::Scored(20.0)]);
+    }
+
+    #[test]
+    fn rejects_files_from_newer_versions() {
+        let data = format!(r#"{{"{}": {}}}"#, VERSION_KEY, SCHEMA_VERSION + 1);
+        assert!(decode_state(&data).is_err_and(|e| e.contains("请升级程序")));
+    }
+}
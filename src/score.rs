@@ -0,0 +1,215 @@
+//! 带状态的分数。
+//!
+//! 分项成绩与期中、期末考试除了分数外，还可以记为未提交、豁免或缺考，
+//! 以便区分 "没有交" "不需要交" 与 "得了 0 分"。各状态如何计分由计算规则决定：
+//! 豁免的分项不参与平均，未提交按规则记 0 分或同样不参与，缺考记 0 分并在等级中单独标出。
+//!
+//! 在 app_state.json 中，有分数的记为数字，其余记为 `"missing"`、`"excused"`、`"absent"`，
+//! 读取时 `null` 视为未提交；在输入区与导出的表格中记为 "未提交"、"豁免"、"缺考"。
+//! 分数必须是不小于 0 的有限数。
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Score {
+    Scored(f32),
+    /// 未提交
+    Missing,
+    /// 豁免，如免交的作业或缓考
+    Excused,
+    /// 缺考
+    Absent,
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        Score::Scored(0.0)
+    }
+}
+
+impl Score {
+    /// 可以设置的状态，不含 `Scored`
+    pub const STATUSES: [Score; 3] = [Score::Missing, Score::Excused, Score::Absent];
+
+    pub fn value(&self) -> Option<f32> {
+        match self {
+            Score::Scored(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn is_scored(&self) -> bool {
+        matches!(self, Score::Scored(_))
+    }
+
+    /// 状态的名称，有分数时为空
+    pub fn label(&self) -> &'static str {
+        match self {
+            Score::Scored(_) => "",
+            Score::Missing => "未提交",
+            Score::Excused => "豁免",
+            Score::Absent => "缺考",
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Score::Scored(_) => "scored",
+            Score::Missing => "missing",
+            Score::Excused => "excused",
+            Score::Absent => "absent",
+        }
+    }
+
+    /// 计入成绩的分数：缺考记 0 分，未提交在 `missing_as_zero` 时记 0 分，其余不计入
+    pub fn counted(&self, missing_as_zero: bool) -> Option<f32> {
+        match self {
+            Score::Scored(value) => Some(*value),
+            Score::Absent => Some(0.0),
+            Score::Missing if missing_as_zero => Some(0.0),
+            Score::Missing | Score::Excused => None,
+        }
+    }
+}
+
+/// 计入成绩的分项的平均分，没有计入的分项时为 0
+pub fn average(scores: &[Score], missing_as_zero: bool) -> f32 {
+    let counted: Vec<f32> = scores.iter().filter_map(|score| score.counted(missing_as_zero)).collect();
+    match counted.len() {
+        0 => 0.0,
+        n => counted.iter().sum::<f32>() / n as f32,
+    }
+}
+
+/// 分项之和，不计入的分项按计入分项的平均分折算，使豁免不影响总分的满分
+pub fn prorated_sum(scores: &[Score], missing_as_zero: bool) -> f32 {
+    average(scores, missing_as_zero) * scores.len() as f32
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Score::Scored(value) => write!(f, "{}", value),
+            _ => f.write_str(self.label()),
+        }
+    }
+}
+
+impl FromStr for Score {
+    type Err = ();
+
+    /// 不小于 0 的数字，或状态的名称，如 "未提交"、"缺交"、"缓考"、"absent"
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        if let Ok(value) = text.parse::<f32>() {
+            return if is_valid(value) { Ok(Score::Scored(value)) } else { Err(()) };
+        }
+        match text.to_lowercase().as_str() {
+            "未提交" | "缺交" | "missing" => Ok(Score::Missing),
+            "豁免" | "免交" | "缓考" | "excused" => Ok(Score::Excused),
+            "缺考" | "absent" => Ok(Score::Absent),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 分数是否有效：有限且不小于 0
+fn is_valid(value: f32) -> bool {
+    value.is_finite() && value >= 0.0
+}
+
+impl Serialize for Score {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Score::Scored(value) => serializer.serialize_f32(*value),
+            _ => serializer.serialize_str(self.key()),
+        }
+    }
+}
+
+struct ScoreVisitor;
+
+impl Visitor<'_> for ScoreVisitor {
+    type Value = Score;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("分数或 \"missing\"、\"excused\"、\"absent\"")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Score, E> {
+        match value as f32 {
+            value if is_valid(value) => Ok(Score::Scored(value)),
+            _ => Err(E::invalid_value(de::Unexpected::Float(value), &self)),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Score, E> {
+        match value {
+            0.. => Ok(Score::Scored(value as f32)),
+            _ => Err(E::invalid_value(de::Unexpected::Signed(value), &self)),
+        }
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Score, E> {
+        Ok(Score::Scored(value as f32))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Score, E> {
+        Score::STATUSES
+            .into_iter()
+            .find(|status| status.key() == value)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+
+    /// 旧版本把 NaN 等无效分数保存为 null，读取为未提交
+    fn visit_unit<E: de::Error>(self) -> Result<Score, E> {
+        Ok(Score::Missing)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Score, E> {
+        Ok(Score::Missing)
+    }
+}
+
+impl<'de> Deserialize<'de> for Score {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ScoreVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_rejects_negative_and_non_finite_numbers() {
+        assert_eq!(" 8.5 ".parse::<Score>(), Ok(Score::Scored(8.5)));
+        assert_eq!("0".parse::<Score>(), Ok(Score::Scored(0.0)));
+        assert_eq!("缓考".parse::<Score>(), Ok(Score::Excused));
+        for text in ["-1", "NaN", "inf", "-inf", "abc"] {
+            assert_eq!(text.parse::<Score>(), Err(()), "{}", text);
+        }
+    }
+
+    #[test]
+    fn json_round_trips_and_reads_null_as_missing() {
+        let scores = vec![Score::Scored(7.5), Score::Missing, Score::Excused, Score::Absent];
+        let json = serde_json::to_string(&scores).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<Score>>(&json).unwrap(), scores);
+        // NaN 会被写成 null
+        let json = serde_json::to_string(&[Score::Scored(f32::NAN)]).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<Score>>(&json).unwrap(), vec![Score::Missing]);
+        assert_eq!(serde_json::from_str::<Score>("3").unwrap(), Score::Scored(3.0));
+    }
+
+    #[test]
+    fn json_rejects_negative_scores() {
+        assert!(serde_json::from_str::<Score>("-1").is_err());
+        assert!(serde_json::from_str::<Score>("-0.5").is_err());
+        assert!(serde_json::from_str::<Score>("\"late\"").is_err());
+    }
+}
@@ -0,0 +1,240 @@
+//! 成绩分布统计。
+//!
+//! 对总评表与 Project 表的每一项成绩计算平均分、中位数、标准差、最值、四分位数、及格率与直方图。
+//! 及格线为该项满分的 60%，没有满分的项目(如 Bonus)不计算及格率。
+//! 期末缺考、缓考的学生不参与总分与考试成绩的统计。
+
+use crate::app_state::{AppState, FinalItem, ProjectItem, ProjectRules, TableType, EXAM_FULL_MARK};
+use crate::score::Score;
+
+/// 及格线占满分的比例
+pub const PASS_RATIO: f32 = 0.6;
+/// 直方图的分段数
+pub const HISTOGRAM_BINS: usize = 10;
+
+/// 参与统计的一项成绩
+pub struct StatField<T> {
+    pub title: &'static str,
+    /// 满分，None 表示没有固定满分
+    pub full_mark: Option<f32>,
+    pub value: fn(&T) -> f32,
+}
+
+/// 考试成绩，缺考等没有分数的学生不参与统计
+fn exam(score: &Score) -> f32 {
+    score.value().unwrap_or(f32::NAN)
+}
+
+/// 总评表参与统计的成绩，作业、讨论、Project 与平时分的满分取自当前规则；
+/// 作业与讨论没有按分项加权折算时没有固定的满分
+pub fn final_fields(state: &AppState) -> Vec<StatField<FinalItem>> {
+    vec![
+        StatField {
+            title: "总分",
+            full_mark: Some(100.0),
+            value: |row| if row.exam_flag().is_some() { f32::NAN } else { row.final_score },
+        },
+        // 未调分时全部为 NaN，不显示该项
+        StatField {
+            title: "调整后总分",
+            full_mark: Some(100.0),
+            value: |row| row.adjusted_score.unwrap_or(f32::NAN),
+        },
+        StatField { title: "绩点", full_mark: None, value: |row| row.grade_point },
+        StatField { title: "期末", full_mark: Some(EXAM_FULL_MARK), value: |row| exam(&row.final_exam_score) },
+        StatField { title: "期中", full_mark: Some(EXAM_FULL_MARK), value: |row| exam(&row.midterm_score) },
+        StatField {
+            title: "作业",
+            full_mark: state.grading_policy.homework_full_mark(),
+            value: |row| row.homework_score_sum,
+        },
+        StatField {
+            title: "讨论",
+            full_mark: state.grading_policy.discussion_full_mark(),
+            value: |row| row.discussion_score_sum,
+        },
+        StatField {
+            title: "Project",
+            full_mark: Some(state.project_rules.total_full_mark()),
+            value: |row| row.project_score_sum,
+        },
+        StatField {
+            title: "平时分",
+            full_mark: Some(state.grading_policy.general_score_cap),
+            value: |row| row.general_score,
+        },
+    ]
+}
+
+/// Project 表参与统计的成绩，满分取自当前规则
+pub fn project_fields(rules: &ProjectRules) -> Vec<StatField<ProjectItem>> {
+    vec![
+        StatField { title: "Project 总分", full_mark: Some(rules.total_full_mark()), value: |row| row.total_score },
+        StatField {
+            title: "展示分数",
+            full_mark: Some(rules.presentation_full_mark),
+            value: |row| row.presentation_score,
+        },
+        StatField {
+            title: "Report 总分",
+            full_mark: Some(rules.report_total_full_mark()),
+            value: |row| row.report_score_sum,
+        },
+        StatField { title: "PR 总分", full_mark: Some(rules.pr_total_full_mark()), value: |row| row.pr_score_sum },
+        StatField { title: "Bonus", full_mark: None, value: |row| row.bonus_score },
+    ]
+}
+
+/// 一项成绩的统计结果
+#[derive(Clone)]
+pub struct Summary {
+    pub title: &'static str,
+    pub count: usize,
+    pub mean: f32,
+    pub median: f32,
+    /// 总体标准差
+    pub std_dev: f32,
+    pub min: f32,
+    pub max: f32,
+    /// 下四分位数与上四分位数
+    pub q1: f32,
+    pub q3: f32,
+    /// 及格人数占比(0 到 1)，没有满分时为 None
+    pub pass_rate: Option<f32>,
+    /// 直方图的范围，没有满分时为最小值到最大值
+    pub range: (f32, f32),
+    /// 每一段的人数，最后一段包含右端点
+    pub histogram: Vec<usize>,
+}
+
+/// 已排序数据的 `p` 分位数(0 到 1)，在相邻两个值之间线性插值
+fn quantile(sorted: &[f32], p: f32) -> f32 {
+    let position = p * (sorted.len() - 1) as f32;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f32)
+}
+
+/// 将 `values` 按 `range` 等分为 `bins` 段计数，超出范围的值计入首段或末段
+pub fn histogram(values: &[f32], range: (f32, f32), bins: usize) -> Vec<usize> {
+    let mut counts = vec![0; bins];
+    let width = (range.1 - range.0) / bins as f32;
+    for value in values {
+        let bin = if width > 0.0 {
+            ((value - range.0) / width).floor().max(0.0) as usize
+        } else {
+            0
+        };
+        counts[bin.min(bins - 1)] += 1;
+    }
+    counts
+}
+
+/// 统计 `values`，忽略 NaN；没有有效数据时返回 None
+pub fn summarize(title: &'static str, values: &[f32], full_mark: Option<f32>) -> Option<Summary> {
+    let mut sorted: Vec<f32> = values.iter().copied().filter(|value| !value.is_nan()).collect();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(f32::total_cmp);
+    let count = sorted.len();
+    let mean = sorted.iter().sum::<f32>() / count as f32;
+    let variance = sorted.iter().map(|value| (value - mean).powi(2)).sum::<f32>() / count as f32;
+    let (min, max) = (sorted[0], sorted[count - 1]);
+    let pass_rate = full_mark.map(|full_mark| {
+        // 按得分率比较，避免 100 × 0.6 的舍入误差使刚好及格的分数被算作不及格
+        let passed = sorted.iter().filter(|&&value| value / full_mark >= PASS_RATIO).count();
+        passed as f32 / count as f32
+    });
+    let range = match full_mark {
+        Some(full_mark) if full_mark > 0.0 => (0.0, full_mark),
+        _ => (min, max),
+    };
+    Some(Summary {
+        title,
+        count,
+        mean,
+        median: quantile(&sorted, 0.5),
+        std_dev: variance.sqrt(),
+        min,
+        max,
+        q1: quantile(&sorted, 0.25),
+        q3: quantile(&sorted, 0.75),
+        pass_rate,
+        range,
+        histogram: histogram(&sorted, range, HISTOGRAM_BINS),
+    })
+}
+
+fn summarize_fields<T>(rows: &[T], fields: Vec<StatField<T>>) -> Vec<Summary> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let values: Vec<f32> = rows.iter().map(field.value).collect();
+            summarize(field.title, &values, field.full_mark)
+        })
+        .collect()
+}
+
+/// `table_type` 对应数据表每一项成绩的统计结果，组队名单没有成绩，返回空列表
+pub fn table_summaries(state: &AppState, table_type: &TableType) -> Vec<Summary> {
+    match table_type {
+        TableType::FinalScore => summarize_fields(&state.final_table, final_fields(state)),
+        TableType::ProjectScore => summarize_fields(&state.project_table, project_fields(&state.project_rules)),
+        TableType::GroupList => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assignment::Assignment;
+
+    #[test]
+    fn quantile_interpolates_between_values() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(quantile(&sorted, 0.0), 1.0);
+        assert_eq!(quantile(&sorted, 0.5), 2.5);
+        assert_eq!(quantile(&sorted, 0.25), 1.75);
+        assert_eq!(quantile(&sorted, 1.0), 4.0);
+        assert_eq!(quantile(&[7.0], 0.75), 7.0);
+    }
+
+    #[test]
+    fn histogram_clamps_values_to_the_end_bins() {
+        let counts = histogram(&[-5.0, 0.0, 9.9, 10.0, 55.0, 100.0, 120.0], (0.0, 100.0), 10);
+        assert_eq!(counts, vec![3, 1, 0, 0, 0, 1, 0, 0, 0, 2]);
+        // 范围为一个点时全部计入首段
+        assert_eq!(histogram(&[3.0, 3.0], (3.0, 3.0), 4), vec![2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn summarize_ignores_nan_and_uses_the_full_mark() {
+        let summary = summarize("期末", &[50.0, f32::NAN, 70.0, 90.0, 60.0], Some(100.0)).unwrap();
+        assert_eq!(summary.count, 4);
+        assert_eq!(summary.mean, 67.5);
+        assert_eq!(summary.median, 65.0);
+        assert_eq!((summary.min, summary.max), (50.0, 90.0));
+        assert_eq!(summary.pass_rate, Some(0.75));
+        assert_eq!(summary.range, (0.0, 100.0));
+        assert_eq!(summary.histogram.iter().sum::<usize>(), 4);
+
+        let summary = summarize("Bonus", &[1.0, 3.0], None).unwrap();
+        assert_eq!((summary.pass_rate, summary.range, summary.std_dev), (None, (1.0, 3.0), 1.0));
+        assert!(summarize("空", &[f32::NAN], Some(10.0)).is_none());
+    }
+
+    #[test]
+    fn item_full_marks_only_apply_when_weighted() {
+        let mut state = AppState::default();
+        let full_mark = |state: &AppState, title: &str| {
+            final_fields(state).into_iter().find(|field| field.title == title).unwrap().full_mark
+        };
+        assert_eq!(full_mark(&state, "作业"), None);
+        state.grading_policy.homework.assignments = vec![Assignment::default()];
+        assert_eq!(full_mark(&state, "作业"), Some(10.0));
+        state.grading_policy.homework.weighted = false;
+        assert_eq!(full_mark(&state, "作业"), None);
+        assert_eq!(full_mark(&state, "Project"), Some(30.0));
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::app_state::*;
+
+/// 同步结果，用于写入输出区域
+#[derive(Default)]
+pub struct SyncReport {
+    pub synced: usize,
+    /// 不在任何组中的学生
+    pub no_group: Vec<String>,
+    /// 同时在多个组中的学生
+    pub multi_group: Vec<String>,
+    /// 组队名单中有、但 Project 表中没有成绩的组
+    pub no_project: Vec<String>,
+}
+
+impl SyncReport {
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!("已同步 {} 名学生的 Project 成绩", self.synced)];
+        if !self.no_group.is_empty() {
+            lines.push(format!("未组队(记为 0): {}", self.no_group.join(",")));
+        }
+        if !self.multi_group.is_empty() {
+            lines.push(format!("同时在多个组中(记为 0): {}", self.multi_group.join(",")));
+        }
+        if !self.no_project.is_empty() {
+            lines.push(format!("Project 表中缺少的组号(组员记为 0): {}", self.no_project.join(",")));
+        }
+        lines.join("\n")
+    }
+}
+
+/// 按组队名单将每组的 Project 总分与 Bonus 写入组员的总评成绩。
+/// 未组队、同时在多个组中或所在组没有 Project 成绩的学生，Project 总分与 Bonus 记为 0
+pub fn sync_project_scores(
+    final_table: &mut [FinalItem],
+    project_table: &[ProjectItem],
+    group_table: &[GroupItem],
+) -> SyncReport {
+    let mut report = SyncReport::default();
+    let mut student_groups: HashMap<&str, Vec<usize>> = HashMap::new();
+    for group in group_table {
+        for member in &group.group_members {
+            student_groups.entry(member.student_id.as_str()).or_default().push(group.group_id);
+        }
+    }
+    let projects: HashMap<usize, &ProjectItem> = project_table.iter().map(|item| (item.group_id, item)).collect();
+
+    let mut no_project: HashSet<usize> = HashSet::new();
+    for student in final_table.iter_mut() {
+        let info = &student.student_info;
+        let label = format!("{} {}", info.student_id, info.student_name);
+        let project = match student_groups.get(info.student_id.as_str()).map(|groups| groups.as_slice()) {
+            None | Some([]) => {
+                report.no_group.push(label);
+                None
+            }
+            Some([group_id]) => {
+                let project = projects.get(group_id);
+                if project.is_none() {
+                    no_project.insert(*group_id);
+                }
+                project
+            }
+            Some(groups) => {
+                let groups: Vec<String> = groups.iter().map(|id| id.to_string()).collect();
+                report.multi_group.push(format!("{}(组 {})", label, groups.join("/")));
+                None
+            }
+        };
+        // 没有对应成绩的学生清零，避免名单调整后保留上次同步的分数
+        student.project_score_sum = project.map_or(0.0, |project| project.total_score);
+        student.bonus_score = project.map_or(0.0, |project| project.bonus_score);
+        if project.is_some() {
+            report.synced += 1;
+        }
+    }
+    let mut no_project: Vec<usize> = no_project.into_iter().collect();
+    no_project.sort();
+    report.no_project = no_project.iter().map(|id| id.to_string()).collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student(id: &str) -> StudentItem {
+        StudentItem {
+            student_id: id.to_string(),
+            student_name: id.to_string(),
+        }
+    }
+
+    fn group(group_id: usize, members: &[&str]) -> GroupItem {
+        GroupItem {
+            group_id,
+            group_members: members.iter().map(|id| student(id)).collect(),
+        }
+    }
+
+    fn project(group_id: usize, total_score: f32, bonus_score: f32) -> ProjectItem {
+        ProjectItem {
+            group_id,
+            total_score,
+            bonus_score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resync_clears_students_without_a_single_scored_group() {
+        let mut final_table: Vec<FinalItem> = ["1", "2", "3", "4"]
+            .iter()
+            .map(|id| FinalItem {
+                student_info: student(id),
+                ..Default::default()
+            })
+            .collect();
+        let projects = [project(1, 25.0, 1.0), project(2, 20.0, 0.5)];
+        let groups = [group(1, &["1", "2"]), group(2, &["3", "4"])];
+        let report = sync_project_scores(&mut final_table, &projects, &groups);
+        assert_eq!(report.synced, 4);
+
+        // 1 离开所有组，2 同时在两个组中，3、4 所在的组没有 Project 成绩
+        let groups = [group(1, &["2"]), group(2, &["2"]), group(3, &["3", "4"])];
+        let report = sync_project_scores(&mut final_table, &projects, &groups);
+        assert_eq!(report.synced, 0);
+        assert_eq!(report.no_group.len(), 1);
+        assert_eq!(report.multi_group.len(), 1);
+        assert_eq!(report.no_project, vec!["3".to_string()]);
+        for row in &final_table {
+            assert_eq!((row.project_score_sum, row.bonus_score), (0.0, 0.0));
+        }
+    }
+}
@@ -0,0 +1,572 @@
+//! 输入区与数据表之间的导入/导出。
+//!
+//! 导入分两步：先由 `preview_import` 解析输入区并与当前表比较，得到新增、修改、删除和解析失败的行；
+//! 确认后再由 `AppState::apply_import` 替换数据表。导出只把表写入输入区，不修改表。
+//!
+//! 输入区使用带表头的 CSV(逗号或制表符分隔，引号规则同 RFC 4180)，分项成绩展开为 作业1, 作业2… 等列。
+//! 以前以 `discussionEnd` 等结束标记分隔的格式仍可导入。
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::app_state::*;
+use crate::parse_error::{parse_cell, ParseError, ParseErrorKind};
+use crate::score::Score;
+use crate::utils::split_to_table;
+
+/// 可以与输入区互相转换的数据表行
+pub trait TableRow: Clone + PartialEq + Sized {
+    /// 用于比较新旧数据的键的名称
+    const KEY_LABEL: &'static str;
+    /// CSV 格式中固定的列，第一列同时用于识别表头
+    const FIELDS: &'static [&'static str];
+    /// CSV 格式中长度可变的分项列。每组前缀按序号展开，如 `["学号", "姓名"]` 展开为 学号1, 姓名1, 学号2…
+    const ITEM_COLUMNS: &'static [&'static [&'static str]];
+    /// 导出时写在固定列之后的计算结果列，如五级制与绩点；导入时忽略，由计算规则重新生成
+    const DERIVED_FIELDS: &'static [&'static str] = &[];
+    fn key(&self) -> String;
+    /// 解析旧的以结束标记分隔的格式，如 `discussionEnd`
+    fn parse_row(row: &[String]) -> Result<Self, ParseError>;
+    /// 与 `FIELDS` 对应的单元格
+    fn fields(&self) -> Vec<String>;
+    /// 与 `DERIVED_FIELDS` 对应的单元格
+    fn derived_cells(&self) -> Vec<String> {
+        vec![]
+    }
+    /// 与 `ITEM_COLUMNS` 对应的分项单元格，按序号依次排列
+    fn item_cells(&self) -> Vec<Vec<String>>;
+    fn parse_record(row: &HeadedRow) -> Result<Self, ParseError>;
+}
+
+impl TableRow for FinalItem {
+    const KEY_LABEL: &'static str = "学号";
+    const FIELDS: &'static [&'static str] = &FINAL_FIELDS;
+    const ITEM_COLUMNS: &'static [&'static [&'static str]] = &[&["讨论"], &["作业"]];
+    const DERIVED_FIELDS: &'static [&'static str] = &["调整后总分", "五级制", "绩点"];
+    fn key(&self) -> String {
+        self.student_info.student_id.clone()
+    }
+    fn parse_row(row: &[String]) -> Result<Self, ParseError> {
+        FinalItem::from_vec(row)
+    }
+    fn fields(&self) -> Vec<String> {
+        self.to_vec()[..FINAL_FIELDS.len()].to_vec()
+    }
+    fn derived_cells(&self) -> Vec<String> {
+        vec![
+            self.adjusted_score.map(|score| score.to_string()).unwrap_or_default(),
+            self.grade_level.clone(),
+            self.grade_point.to_string(),
+        ]
+    }
+    fn item_cells(&self) -> Vec<Vec<String>> {
+        vec![number_cells(&self.discussion_score), number_cells(&self.homework_score)]
+    }
+    fn parse_record(row: &HeadedRow) -> Result<Self, ParseError> {
+        Ok(Self {
+            student_info: StudentItem {
+                student_id: row.key()?,
+                student_name: row.text(FINAL_FIELDS[1]),
+            },
+            final_score: row.number(FINAL_FIELDS[2])?,
+            general_score: row.number(FINAL_FIELDS[3])?,
+            final_exam_score: row.number(FINAL_FIELDS[4])?,
+            project_score_sum: row.number(FINAL_FIELDS[5])?,
+            midterm_score: row.number(FINAL_FIELDS[6])?,
+            homework_score_sum: row.number(FINAL_FIELDS[7])?,
+            discussion_score_sum: row.number(FINAL_FIELDS[8])?,
+            bonus_score: row.number(FINAL_FIELDS[9])?,
+            discussion_score: row.items("讨论")?,
+            homework_score: row.items("作业")?,
+            ..Default::default()
+        })
+    }
+}
+
+impl TableRow for ProjectItem {
+    const KEY_LABEL: &'static str = "组号";
+    const FIELDS: &'static [&'static str] = &PROJECT_FIELDS;
+    const ITEM_COLUMNS: &'static [&'static [&'static str]] = &[&["Report"], &["PR"]];
+    fn key(&self) -> String {
+        self.group_id.to_string()
+    }
+    fn parse_row(row: &[String]) -> Result<Self, ParseError> {
+        ProjectItem::from_vec(row)
+    }
+    fn fields(&self) -> Vec<String> {
+        self.to_vec()[..PROJECT_FIELDS.len()].to_vec()
+    }
+    fn item_cells(&self) -> Vec<Vec<String>> {
+        vec![number_cells(&self.report_score), number_cells(&self.pr_score)]
+    }
+    fn parse_record(row: &HeadedRow) -> Result<Self, ParseError> {
+        Ok(Self {
+            group_id: row.key_number()?,
+            total_score: row.number(PROJECT_FIELDS[1])?,
+            presentation_score: row.number(PROJECT_FIELDS[2])?,
+            bonus_score: row.number(PROJECT_FIELDS[3])?,
+            report_score_sum: row.number(PROJECT_FIELDS[4])?,
+            pr_score_sum: row.number(PROJECT_FIELDS[5])?,
+            presentation_id: row.number(PROJECT_FIELDS[6])?,
+            report_score: row.items("Report")?,
+            pr_score: row.items("PR")?,
+        })
+    }
+}
+
+impl TableRow for GroupItem {
+    const KEY_LABEL: &'static str = "组号";
+    const FIELDS: &'static [&'static str] = &["组号"];
+    const ITEM_COLUMNS: &'static [&'static [&'static str]] = &[&["学号", "姓名"]];
+    fn key(&self) -> String {
+        self.group_id.to_string()
+    }
+    fn parse_row(row: &[String]) -> Result<Self, ParseError> {
+        GroupItem::from_vec(row)
+    }
+    fn fields(&self) -> Vec<String> {
+        vec![self.group_id.to_string()]
+    }
+    fn item_cells(&self) -> Vec<Vec<String>> {
+        vec![self.to_vec()[1..].to_vec()]
+    }
+    fn parse_record(row: &HeadedRow) -> Result<Self, ParseError> {
+        let ids = row.item_texts("学号");
+        let names = row.item_texts("姓名");
+        let mut group_members = Vec::new();
+        for i in 0..ids.len().max(names.len()) {
+            let student_id = ids.get(i).cloned().unwrap_or_default();
+            if student_id.is_empty() {
+                let field = format!("学号{}", i + 1);
+                return Err(ParseError::new(ParseErrorKind::Incomplete, row.column(&field), &field, ""));
+            }
+            group_members.push(StudentItem {
+                student_id,
+                student_name: names.get(i).cloned().unwrap_or_default(),
+            });
+        }
+        Ok(Self {
+            group_id: row.key_number()?,
+            group_members,
+        })
+    }
+}
+
+fn number_cells(scores: &[Score]) -> Vec<String> {
+    scores.iter().map(|score| score.to_string()).collect()
+}
+
+/// CSV 格式中的一行，按表头名读取单元格。缺少的列与空单元格视为 0，空的分项单元格视为未提交
+pub struct HeadedRow<'a> {
+    headers: &'a [String],
+    cells: &'a [String],
+}
+
+impl HeadedRow<'_> {
+    fn position(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|header| header == name)
+    }
+
+    /// 列号，从 1 开始；没有该列时为 0
+    fn column(&self, name: &str) -> usize {
+        self.position(name).map_or(0, |index| index + 1)
+    }
+
+    fn cell(&self, index: usize) -> &str {
+        self.cells.get(index).map_or("", |cell| cell.as_str())
+    }
+
+    /// 第一列(学号或组号)，不能为空
+    fn key(&self) -> Result<String, ParseError> {
+        let key = self.cell(0);
+        if key.is_empty() {
+            return Err(ParseError::new(ParseErrorKind::Incomplete, 1, &self.headers[0], ""));
+        }
+        Ok(key.to_string())
+    }
+
+    fn key_number<T: FromStr>(&self) -> Result<T, ParseError> {
+        self.key()?;
+        parse_cell(self.cells, 0, &self.headers[0])
+    }
+
+    fn text(&self, name: &str) -> String {
+        self.position(name).map(|index| self.cell(index).to_string()).unwrap_or_default()
+    }
+
+    fn number<T: FromStr + Default>(&self, name: &str) -> Result<T, ParseError> {
+        match self.position(name) {
+            Some(index) if !self.cell(index).is_empty() => parse_cell(self.cells, index, name),
+            _ => Ok(T::default()),
+        }
+    }
+
+    /// 按序号排列的 `prefix1`, `prefix2`… 列的下标
+    fn item_positions(&self, prefix: &str) -> Vec<usize> {
+        let mut columns: Vec<(usize, usize)> = self
+            .headers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, header)| Some((header.strip_prefix(prefix)?.parse::<usize>().ok()?, index)))
+            .collect();
+        columns.sort();
+        columns.into_iter().map(|(_, index)| index).collect()
+    }
+
+    /// 去掉行末空单元格后的分项列下标。各行分项数量或各组人数不同时，导出的较短的行末尾为空，
+    /// 这些单元格表示没有该分项，而不是未提交(未提交写为 "未提交")
+    fn filled_positions(&self, prefix: &str) -> Vec<usize> {
+        let mut positions = self.item_positions(prefix);
+        while positions.last().is_some_and(|&index| self.cell(index).is_empty()) {
+            positions.pop();
+        }
+        positions
+    }
+
+    fn item_texts(&self, prefix: &str) -> Vec<String> {
+        self.filled_positions(prefix)
+            .into_iter()
+            .map(|index| self.cell(index).to_string())
+            .collect()
+    }
+
+    /// 中间的空单元格视为未提交
+    fn items(&self, prefix: &str) -> Result<Vec<Score>, ParseError> {
+        self.filled_positions(prefix)
+            .into_iter()
+            .map(|index| match self.cell(index) {
+                "" => Ok(Score::Missing),
+                _ => parse_cell(self.cells, index, &self.headers[index]),
+            })
+            .collect()
+    }
+}
+
+/// 解析成功、等待确认的数据
+pub enum PendingRows {
+    Final(Vec<FinalItem>),
+    Project(Vec<ProjectItem>),
+    Group(Vec<GroupItem>),
+}
+
+/// 导入预览。确认前不会修改数据表
+pub struct ImportPreview {
+    pub table_type: TableType,
+    pub key_label: &'static str,
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+    /// 解析失败的行，按行号排列
+    pub failed: Vec<ParseError>,
+    rows: PendingRows,
+}
+
+impl ImportPreview {
+    pub fn row_count(&self) -> usize {
+        match &self.rows {
+            PendingRows::Final(rows) => rows.len(),
+            PendingRows::Project(rows) => rows.len(),
+            PendingRows::Group(rows) => rows.len(),
+        }
+    }
+
+    /// 解析失败的行号，从 1 开始
+    pub fn failed_lines(&self) -> Vec<usize> {
+        self.failed.iter().map(|e| e.line).collect()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "共 {} 行: 新增 {}, 修改 {}, 删除 {}, 解析失败 {}",
+            self.row_count(),
+            self.added.len(),
+            self.changed.len(),
+            self.removed.len(),
+            self.failed.len()
+        )
+    }
+}
+
+/// 收集解析成功的行；解析失败或键重复的行记入 `failed`。行号从 1 开始
+fn collect_rows<T: TableRow>(
+    parsed: impl Iterator<Item = (usize, Result<T, ParseError>)>,
+    failed: &mut Vec<ParseError>,
+) -> Vec<T> {
+    let mut keys = HashSet::new();
+    let mut rows = vec![];
+    for (line, result) in parsed {
+        match result {
+            Ok(item) if !keys.insert(item.key()) => {
+                let error = ParseError::new(ParseErrorKind::Duplicate, 1, T::KEY_LABEL, &item.key());
+                failed.push(error.at_line(line));
+            }
+            Ok(item) => rows.push(item),
+            Err(e) => failed.push(e.at_line(line)),
+        }
+    }
+    rows
+}
+
+/// 旧格式：逐行以逗号或制表符分割，跳过空行
+fn parse_legacy_rows<T: TableRow>(text: &str, failed: &mut Vec<ParseError>) -> Vec<T> {
+    let table = split_to_table(text.to_string());
+    let parsed = table
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| !row.iter().all(|cell| cell.is_empty()))
+        .map(|(index, row)| (index + 1, T::parse_row(row)));
+    collect_rows(parsed, failed)
+}
+
+/// 读取 CSV 记录及其起始行号。首行含制表符时按 TSV 读取
+fn read_records(text: &str) -> Vec<(usize, Vec<String>)> {
+    let first_line = text.lines().next().unwrap_or_default();
+    let delimiter = if first_line.contains('\t') { b'\t' } else { b',' };
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+    // 文本来自 &str，不会出现编码错误；flexible 模式下也不检查列数
+    reader
+        .records()
+        .map_while(Result::ok)
+        .map(|record| {
+            let line = record.position().map_or(0, |position| position.line() as usize);
+            (line, record.iter().map(str::to_string).collect())
+        })
+        .collect()
+}
+
+/// 第一个非空单元格为 `T::FIELDS[0]` 时视为带表头的 CSV 格式
+fn is_headed<T: TableRow>(text: &str) -> bool {
+    text.trim_start_matches('\u{feff}')
+        .trim_start()
+        .split([',', '\t', '\n'])
+        .next()
+        .is_some_and(|cell| cell.trim().trim_matches('"') == T::FIELDS[0])
+}
+
+/// CSV 格式：首行为表头，其余每行一条数据
+fn parse_headed_rows<T: TableRow>(text: &str, failed: &mut Vec<ParseError>) -> Vec<T> {
+    let records = read_records(text.trim_start_matches('\u{feff}'));
+    let Some(((_, headers), records)) = records.split_first() else {
+        return vec![];
+    };
+    let parsed = records
+        .iter()
+        .filter(|(_, cells)| !cells.iter().all(|cell| cell.is_empty()))
+        .map(|(line, cells)| (*line, T::parse_record(&HeadedRow { headers, cells })));
+    collect_rows(parsed, failed)
+}
+
+/// 按格式解析输入区文本：带表头的 CSV，或旧的以结束标记分隔的格式
+fn parse_rows<T: TableRow>(text: &str, failed: &mut Vec<ParseError>) -> Vec<T> {
+    if is_headed::<T>(text) {
+        parse_headed_rows(text, failed)
+    } else {
+        parse_legacy_rows(text, failed)
+    }
+}
+
+/// 按键比较新旧两张表，返回 (新增, 修改, 删除) 的键
+fn diff<T: TableRow>(old: &[T], new: &[T]) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let old_rows: HashMap<String, &T> = old.iter().map(|row| (row.key(), row)).collect();
+    let new_keys: HashSet<String> = new.iter().map(|row| row.key()).collect();
+    let mut added = vec![];
+    let mut changed = vec![];
+    for row in new {
+        match old_rows.get(&row.key()) {
+            None => added.push(row.key()),
+            Some(old_row) if *old_row != row => changed.push(row.key()),
+            Some(_) => {}
+        }
+    }
+    let removed = old
+        .iter()
+        .map(|row| row.key())
+        .filter(|key| !new_keys.contains(key))
+        .collect();
+    (added, changed, removed)
+}
+
+/// `prepare` 在比较前生成每一行的计算结果列，使未修改的行与当前表相等
+fn build_preview<T: TableRow>(
+    table_type: TableType,
+    text: &str,
+    old: &[T],
+    wrap: fn(Vec<T>) -> PendingRows,
+    prepare: impl Fn(&mut T),
+) -> ImportPreview {
+    let mut failed = vec![];
+    let mut rows = parse_rows::<T>(text, &mut failed);
+    rows.iter_mut().for_each(prepare);
+    let (added, changed, removed) = diff(old, &rows);
+    ImportPreview {
+        table_type,
+        key_label: T::KEY_LABEL,
+        added,
+        changed,
+        removed,
+        failed,
+        rows: wrap(rows),
+    }
+}
+
+/// 解析输入区格式的文本，生成导入到 `table_type` 对应数据表的预览
+pub fn preview_import(state: &AppState, table_type: TableType, text: &str) -> ImportPreview {
+    match table_type {
+        // 文本中没有提交时间，沿用当前表中同一学号的记录
+        TableType::FinalScore => build_preview(table_type, text, &state.final_table, PendingRows::Final, |row| {
+            if let Some(old) = state.final_table.iter().find(|old| old.key() == row.key()) {
+                row.homework_submitted = old.homework_submitted.clone();
+            }
+            row.update_reported(&state.grading_policy, state.curve.applied, &state.grade_scale)
+        }),
+        TableType::ProjectScore => {
+            build_preview(table_type, text, &state.project_table, PendingRows::Project, |_| {})
+        }
+        TableType::GroupList => build_preview(table_type, text, &state.group_table, PendingRows::Group, |_| {}),
+    }
+}
+
+/// 将数据表转换为带表头的 CSV，分项成绩按最多的一行展开为 作业1, 作业2…
+pub fn table_to_text<T: TableRow>(table: &[T]) -> String {
+    let cells: Vec<(Vec<String>, Vec<Vec<String>>)> = table
+        .iter()
+        .map(|row| ([row.fields(), row.derived_cells()].concat(), row.item_cells()))
+        .collect();
+    let mut headers: Vec<String> = T::FIELDS
+        .iter()
+        .chain(T::DERIVED_FIELDS)
+        .map(|field| field.to_string())
+        .collect();
+    let mut widths = vec![];
+    for (i, prefixes) in T::ITEM_COLUMNS.iter().enumerate() {
+        let width = cells.iter().map(|(_, items)| items[i].len()).max().unwrap_or(0);
+        let count = width.div_ceil(prefixes.len());
+        for n in 1..=count {
+            headers.extend(prefixes.iter().map(|prefix| format!("{}{}", prefix, n)));
+        }
+        widths.push(count * prefixes.len());
+    }
+
+    let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(vec![]);
+    let _ = writer.write_record(&headers);
+    for (fields, items) in cells {
+        let mut record = fields;
+        for (mut item, width) in items.into_iter().zip(&widths) {
+            item.resize(*width, String::new());
+            record.extend(item);
+        }
+        let _ = writer.write_record(&record);
+    }
+    let data = writer.into_inner().unwrap_or_default();
+    String::from_utf8(data).unwrap_or_default()
+}
+
+/// 将 `table_type` 对应的数据表转换为输入区格式
+pub fn export_table_text(state: &AppState, table_type: &TableType) -> String {
+    match table_type {
+        TableType::FinalScore => table_to_text(&state.final_table),
+        TableType::ProjectScore => table_to_text(&state.project_table),
+        TableType::GroupList => table_to_text(&state.group_table),
+    }
+}
+
+/// 检查键是否为空或重复，返回第一个问题的描述
+fn check_rows<T: TableRow>(table: &[T]) -> Result<(), String> {
+    let mut keys = HashSet::new();
+    for row in table {
+        let key = row.key();
+        if key.is_empty() {
+            return Err(format!("{}不能为空", T::KEY_LABEL));
+        }
+        if !keys.insert(key) {
+            return Err(format!("重复的{}: {}", T::KEY_LABEL, row.key()));
+        }
+    }
+    Ok(())
+}
+
+impl AppState {
+    /// 检查 `table_type` 对应数据表的学号/组号是否为空或重复
+    pub fn check_keys(&self, table_type: &TableType) -> Result<(), String> {
+        match table_type {
+            TableType::FinalScore => check_rows(&self.final_table),
+            TableType::ProjectScore => check_rows(&self.project_table),
+            TableType::GroupList => check_rows(&self.group_table),
+        }
+    }
+
+    /// 用预览中解析成功的行替换对应的数据表
+    pub fn apply_import(&mut self, preview: ImportPreview) {
+        match preview.rows {
+            PendingRows::Final(rows) => self.final_table = rows,
+            PendingRows::Project(rows) => self.project_table = rows,
+            PendingRows::Group(rows) => self.group_table = rows,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|text| text.to_string()).collect()
+    }
+
+    #[test]
+    fn trailing_blank_items_are_absent_and_inner_blanks_missing() {
+        let headers = strings(&["学号", "作业2", "作业1", "作业3", "作业4", "讨论1"]);
+        let cells = strings(&["1", "", "8", "未提交", ""]);
+        let row = HeadedRow {
+            headers: &headers,
+            cells: &cells,
+        };
+        let homework = row.items("作业").unwrap();
+        assert_eq!(homework, vec![Score::Scored(8.0), Score::Missing, Score::Missing]);
+        assert_eq!(row.items("讨论").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn export_then_import_is_unchanged() {
+        let student = |id: &str, homework: Vec<Score>| FinalItem {
+            student_info: StudentItem {
+                student_id: id.to_string(),
+                student_name: id.to_string(),
+            },
+            homework_score: homework,
+            discussion_score: vec![Score::Scored(1.0)],
+            ..Default::default()
+        };
+        let mut state = AppState {
+            final_table: vec![
+                student("1", vec![Score::Scored(5.0)]),
+                student("2", vec![Score::Scored(1.0), Score::Missing, Score::Excused]),
+                student("3", vec![]),
+            ],
+            ..Default::default()
+        };
+        state.update_reported_scores();
+        let text = export_table_text(&state, &TableType::FinalScore);
+        let preview = preview_import(&state, TableType::FinalScore, &text);
+        assert!(preview.failed.is_empty());
+        assert!(preview.added.is_empty() && preview.removed.is_empty());
+        assert!(preview.changed.is_empty(), "{:?}", preview.changed);
+    }
+
+    #[test]
+    fn group_members_ignore_trailing_blank_cells() {
+        let headers = strings(&["组号", "学号1", "姓名1", "学号2", "姓名2"]);
+        let cells = strings(&["1", "1001", "张三", "", ""]);
+        let row = HeadedRow {
+            headers: &headers,
+            cells: &cells,
+        };
+        let group = GroupItem::parse_record(&row).unwrap();
+        assert_eq!(group.group_members.len(), 1);
+    }
+}
@@ -0,0 +1,266 @@
+//! 数据表的排序、筛选与搜索。
+//!
+//! 每张表的 `TableView` 记录排序列、每一列的筛选条件与搜索词，
+//! `TableView::visible_rows` 据此返回需要显示的行的下标，不修改数据表本身。
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::TableType;
+use crate::columns::{Cell, TableColumn, TableModel};
+use crate::pinyin::initials;
+use crate::score::Score;
+
+/// 表格中一个单元格用于排序与筛选的值
+enum CellValue {
+    Text(String),
+    Number(f64),
+}
+
+impl CellValue {
+    fn compare(&self, other: &CellValue) -> Ordering {
+        match (self, other) {
+            (CellValue::Number(a), CellValue::Number(b)) => a.total_cmp(b),
+            (CellValue::Text(a), CellValue::Text(b)) => a.cmp(b),
+            (CellValue::Number(_), CellValue::Text(_)) => Ordering::Less,
+            (CellValue::Text(_), CellValue::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// 单元格用于排序与筛选的值，空单元格返回 None
+fn cell_value(cell: Cell) -> Option<CellValue> {
+    match cell {
+        Cell::Text(text) => Some(CellValue::Text(text.to_string())),
+        Cell::Number(value) => Some(CellValue::Number(value as f64)),
+        Cell::Index(value) => Some(CellValue::Number(value as f64)),
+        Cell::Score(Score::Scored(value)) => Some(CellValue::Number(value as f64)),
+        // 未提交、缺考等状态按名称筛选，排序时排在分数之后
+        Cell::Score(status) => Some(CellValue::Text(status.label().to_string())),
+        Cell::Empty => None,
+    }
+}
+
+fn value_at(values: &[Option<CellValue>], col: usize) -> Option<&CellValue> {
+    values.get(col).and_then(Option::as_ref)
+}
+
+/// 解析后的筛选条件
+enum Condition {
+    Compare(&'static str, f64),
+    /// 不带比较符的条件：数字列按相等比较，文本列按包含比较
+    Plain(String),
+}
+
+/// 支持的比较符，较长的在前以免 "<=" 被当作 "<"
+const OPERATORS: [&str; 7] = ["<=", ">=", "!=", "==", "<", ">", "="];
+
+impl Condition {
+    fn parse(filter: &str) -> Option<Self> {
+        let filter = filter.trim();
+        for op in OPERATORS {
+            if let Some(rest) = filter.strip_prefix(op) {
+                return rest.trim().parse().ok().map(|value| Condition::Compare(op, value));
+            }
+        }
+        Some(Condition::Plain(filter.to_lowercase()))
+    }
+
+    fn matches(&self, value: &CellValue) -> bool {
+        match (self, value) {
+            (Condition::Compare(op, target), CellValue::Number(value)) => match *op {
+                "<" => value < target,
+                "<=" => value <= target,
+                ">" => value > target,
+                ">=" => value >= target,
+                "!=" => (value - target).abs() > f64::EPSILON,
+                _ => (value - target).abs() <= f64::EPSILON,
+            },
+            (Condition::Compare(..), CellValue::Text(_)) => false,
+            (Condition::Plain(text), CellValue::Number(value)) => match text.parse::<f64>() {
+                Ok(target) => (value - target).abs() <= f64::EPSILON,
+                Err(_) => false,
+            },
+            (Condition::Plain(text), CellValue::Text(value)) => value.to_lowercase().contains(text),
+        }
+    }
+}
+
+/// 筛选条件是否可以解析，如 "< 60"、">= 90"、"= 12" 或任意文本
+pub fn is_valid_filter(filter: &str) -> bool {
+    Condition::parse(filter).is_some()
+}
+
+/// 搜索词是否匹配：学号或姓名包含搜索词，或姓名的拼音首字母包含搜索词
+fn search_matches(keys: &[String], query: &str) -> bool {
+    keys.iter().any(|key| {
+        key.to_lowercase().contains(query)
+            || (query.chars().all(|c| c.is_ascii_alphabetic()) && initials(key).contains(query))
+    })
+}
+
+/// 一张表的排序、筛选与搜索状态。列按 `TableColumn::id` 记录，
+/// 调分后插入 "调整后总分" 列或分项数量变化时，排序与筛选仍对应原来的列
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TableView {
+    /// 排序的列与是否降序，None 表示按原顺序
+    pub sort: Option<(String, bool)>,
+    /// 各列的筛选条件，没有记录或为空字符串表示不筛选
+    pub filters: BTreeMap<String, String>,
+    pub search: String,
+}
+
+impl TableView {
+    /// 依次切换为升序、降序、原顺序
+    pub fn toggle_sort(&mut self, column: &str) {
+        self.sort = match self.sort.take() {
+            Some((col, false)) if col == column => Some((col, true)),
+            Some((col, true)) if col == column => None,
+            _ => Some((column.to_string(), false)),
+        };
+    }
+
+    /// `column` 列是否按升序(false)或降序(true)排序
+    pub fn sort_of(&self, column: &str) -> Option<bool> {
+        self.sort.as_ref().filter(|(col, _)| col == column).map(|(_, descending)| *descending)
+    }
+
+    pub fn filter_mut(&mut self, column: &str) -> &mut String {
+        self.filters.entry(column.to_string()).or_default()
+    }
+
+    /// 去掉空的筛选条件
+    pub fn remove_empty_filters(&mut self) {
+        self.filters.retain(|_, filter| !filter.trim().is_empty());
+    }
+
+    /// 是否设置了筛选或搜索
+    pub fn is_filtered(&self) -> bool {
+        !self.search.trim().is_empty() || self.filters.values().any(|filter| !filter.trim().is_empty())
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 筛选并排序后需要显示的行的下标。`columns` 中没有的列上的排序与筛选不起作用
+    pub fn visible_rows<T: TableModel>(&self, rows: &[T], columns: &[TableColumn<T::Key>]) -> Vec<usize> {
+        let query = self.search.trim().to_lowercase();
+        let position = |id: &str| columns.iter().position(|column| column.id == id);
+        let conditions: Vec<(usize, Condition)> = self
+            .filters
+            .iter()
+            .filter(|(_, filter)| !filter.trim().is_empty())
+            .filter_map(|(id, filter)| Some((position(id)?, Condition::parse(filter)?)))
+            .collect();
+        let mut visible: Vec<(usize, Vec<Option<CellValue>>)> = rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| query.is_empty() || search_matches(&row.search_keys(), &query))
+            .map(|(index, row)| {
+                let values: Vec<Option<CellValue>> =
+                    columns.iter().map(|column| cell_value(row.cell(column.key))).collect();
+                (index, values)
+            })
+            .filter(|(_, values)| {
+                conditions
+                    .iter()
+                    .all(|(col, condition)| value_at(values, *col).is_some_and(|value| condition.matches(value)))
+            })
+            .collect();
+        let sort = self.sort.as_ref().and_then(|(id, descending)| Some((position(id)?, *descending)));
+        if let Some((col, descending)) = sort {
+            visible.sort_by(|(_, a), (_, b)| {
+                // 空单元格排在最后
+                let ordering = match (value_at(a, col), value_at(b, col)) {
+                    (Some(a), Some(b)) => a.compare(b),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                };
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+        visible.into_iter().map(|(index, _)| index).collect()
+    }
+}
+
+/// 三张表各自的排序、筛选与搜索状态
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TableViews {
+    pub final_score: TableView,
+    pub project_score: TableView,
+    pub group_list: TableView,
+}
+
+impl TableViews {
+    pub fn get_mut(&mut self, table_type: &TableType) -> &mut TableView {
+        match table_type {
+            TableType::FinalScore => &mut self.final_score,
+            TableType::ProjectScore => &mut self.project_score,
+            TableType::GroupList => &mut self.group_list,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{FinalItem, GradingPolicy, ProjectRules, StudentItem};
+
+    fn matches(filter: &str, value: CellValue) -> bool {
+        Condition::parse(filter).unwrap().matches(&value)
+    }
+
+    #[test]
+    fn conditions_compare_numbers_and_search_text() {
+        assert!(matches("<= 60", CellValue::Number(60.0)));
+        assert!(!matches("<60", CellValue::Number(60.0)));
+        assert!(matches(">=90", CellValue::Number(95.0)));
+        assert!(matches("!= 1", CellValue::Number(2.0)));
+        assert!(matches("= 12", CellValue::Number(12.0)));
+        assert!(matches("12", CellValue::Number(12.0)));
+        assert!(!matches("> 1", CellValue::Text("未提交".to_string())));
+        assert!(matches("zhang", CellValue::Text("Zhang San".to_string())));
+        assert!(is_valid_filter("abc"));
+        assert!(!is_valid_filter("< abc"));
+    }
+
+    fn student(id: &str, name: &str, final_score: f32, adjusted_score: Option<f32>) -> FinalItem {
+        FinalItem {
+            student_info: StudentItem {
+                student_id: id.to_string(),
+                student_name: name.to_string(),
+            },
+            final_score,
+            adjusted_score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sort_and_filters_follow_columns_not_positions() {
+        let mut rows = vec![student("1", "张三", 70.0, None), student("2", "李四", 50.0, None)];
+        let (policy, rules) = (GradingPolicy::default(), ProjectRules::default());
+        let columns = FinalItem::columns(&rows, &policy, &rules);
+        let total = columns.iter().find(|column| column.title == "总分").unwrap().id.clone();
+        let mut view = TableView::default();
+        view.toggle_sort(&total);
+        *view.filter_mut(&total) = "< 80".to_string();
+        assert_eq!(view.visible_rows(&rows, &columns), vec![1, 0]);
+
+        // 调分后插入 "调整后总分" 列，排序与筛选仍作用于总分
+        rows.iter_mut().for_each(|row| row.adjusted_score = Some(90.0));
+        let columns = FinalItem::columns(&rows, &policy, &rules);
+        assert_eq!(view.visible_rows(&rows, &columns), vec![1, 0]);
+
+        view.search = "zs".to_string();
+        assert_eq!(view.visible_rows(&rows, &columns), vec![0]);
+    }
+}
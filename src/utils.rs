@@ -1,30 +1,11 @@
+use crate::paste::{detect_delimiter, normalize_text, split_cells};
 
-
-pub fn load_fonts(ctx: &egui::Context) {
-    let mut fonts = egui::FontDefinitions::default();
-    fonts.font_data.insert("my_font".to_owned(),
-    egui::FontData::from_static(include_bytes!("fonts/hei.ttf")));
-    fonts.families.get_mut(&egui::FontFamily::Proportional).unwrap()
-        .insert(0, "my_font".to_owned());
-    fonts.families.get_mut(&egui::FontFamily::Monospace).unwrap()
-        .push("my_font".to_owned());
-    ctx.set_fonts(fonts);
-}
-
+/// 按行分割输入区文本，每行对应一个元素(包括空行)。分隔符自动识别，支持引号与全角标点
 pub fn split_to_table(input_text: String) -> Vec<Vec<String>>{
+    let input_text = normalize_text(&input_text);
+    let delimiter = detect_delimiter(&input_text);
     input_text
         .lines()
-        .map(|line| {
-            line.split(|c| c == ',' || c == '\t')
-                .map(|s| s.trim().to_string())
-                .collect()
-        })
+        .map(|line| split_cells(line, delimiter))
         .collect()
 }
-
-pub fn vec_to_string(vec: Vec<Vec<String>>) -> String {
-    vec.iter()
-        .map(|row| row.join(", "))
-        .collect::<Vec<String>>()
-        .join("\n")
-}
\ No newline at end of file